@@ -1,36 +1,84 @@
 use std::path::PathBuf;
 
-pub const REVERS_DOMAIN: &'static str = "com.webspence.kd";
+use crate::error::Error;
+
+pub const REVERS_DOMAIN: &str = "com.webspence.kd";
+
+/// Overrides the data directory, `--data-dir` sets it too
+pub const DATA_DIR_VAR: &str = "KD_DATA_DIR";
+/// Overrides the config directory, `--config-dir` sets it too
+pub const CONFIG_DIR_VAR: &str = "KD_CONFIG_DIR";
+/// Selects a named library, `--library` sets it too
+pub const LIBRARY_VAR: &str = "KD_LIBRARY";
+
+pub const DEFAULT_LIBRARY: &str = "default";
+/// Directory under the data directory holding the named libraries
+const LIBRARIES_DIR: &str = "libraries";
+/// Used in place of the platform directories when they can't be found, e.g. without HOME
+const FALLBACK_DIR: &str = ".kd";
 
 pub struct Config {
     config_dir: PathBuf,
     data_dir: PathBuf,
+    library: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Config {
+    /// Locations from the environment, falling back to the platform directories
     pub fn new() -> Config {
-        let config_dir = dirs::config_dir()
-            .unwrap()
-            .join(format!("{REVERS_DOMAIN}/config"));
-        let data_dir = dirs::data_local_dir()
-            .unwrap()
-            .join(format!("{REVERS_DOMAIN}/data"));
+        let var = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty());
+        Config::resolve(
+            var(DATA_DIR_VAR).map(PathBuf::from),
+            var(CONFIG_DIR_VAR).map(PathBuf::from),
+            var(LIBRARY_VAR).map(|library| library.to_string_lossy().to_string()),
+        )
+    }
+
+    /// Locations given explicit overrides. Unset ones come from the platform directories
+    pub fn resolve(
+        data_dir: Option<PathBuf>,
+        config_dir: Option<PathBuf>,
+        library: Option<String>,
+    ) -> Config {
+        let config_dir = config_dir.unwrap_or_else(|| match dirs::config_dir() {
+            Some(dir) => dir.join(format!("{REVERS_DOMAIN}/config")),
+            None => PathBuf::from(FALLBACK_DIR).join("config"),
+        });
+        let data_dir = data_dir.unwrap_or_else(|| match dirs::data_local_dir() {
+            Some(dir) => dir.join(format!("{REVERS_DOMAIN}/data")),
+            None => PathBuf::from(FALLBACK_DIR).join("data"),
+        });
 
         Config {
             config_dir,
             data_dir,
+            library: library.unwrap_or_else(|| DEFAULT_LIBRARY.to_string()),
         }
     }
-    pub fn init(&self) -> Result<(), String> {
+
+    pub fn init(&self) -> Result<(), Error> {
+        if !is_valid_library_name(&self.library) {
+            return Err(Error::Validation(format!(
+                "Invalid library name '{}', use letters, digits, '-' and '_'",
+                self.library
+            )));
+        }
+
         // Set up config directory
         if !&self.config_dir.exists() {
-            if let Err(_) = std::fs::create_dir_all(&self.config_dir) {
-                return Err(format!(
+            if std::fs::create_dir_all(&self.config_dir).is_err() {
+                return Err(Error::Io(format!(
                     "Unabled to create config directory: {:?}",
                     self.config_dir.as_os_str()
-                ));
+                )));
             } else {
-                println!(
+                eprintln!(
                     "Created config directory: {:?}",
                     self.config_dir.as_os_str()
                 );
@@ -38,14 +86,15 @@ impl Config {
         }
 
         // Set up app data directory
-        if !self.data_dir.exists() {
-            if let Err(_) = std::fs::create_dir_all(&self.data_dir) {
-                return Err(format!(
+        let data_dir = self.get_data_dir();
+        if !data_dir.exists() {
+            if std::fs::create_dir_all(&data_dir).is_err() {
+                return Err(Error::Io(format!(
                     "Unabled to create data directory: {:?}",
-                    self.data_dir.as_os_str()
-                ));
+                    data_dir.as_os_str()
+                )));
             } else {
-                println!("Created data directory: {:?}", self.data_dir.as_os_str());
+                eprintln!("Created data directory: {:?}", data_dir.as_os_str());
             }
         }
         Ok(())
@@ -55,7 +104,68 @@ impl Config {
         &self.config_dir
     }
 
-    pub fn get_data_dir(&self) -> &PathBuf {
-        &self.data_dir
+    /// Directory of the selected library. The default library lives directly in the data
+    /// directory so existing collections keep working
+    pub fn get_data_dir(&self) -> PathBuf {
+        if self.library == DEFAULT_LIBRARY {
+            self.data_dir.clone()
+        } else {
+            self.data_dir.join(LIBRARIES_DIR).join(&self.library)
+        }
+    }
+
+    pub fn get_library(&self) -> &str {
+        &self.library
+    }
+
+    /// Names of every library that has been created, the default one first
+    pub fn get_libraries(&self) -> Vec<String> {
+        let mut libraries: Vec<String> = std::fs::read_dir(self.data_dir.join(LIBRARIES_DIR))
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .filter(|entry| entry.path().is_dir())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| is_valid_library_name(name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        libraries.sort();
+        libraries.insert(0, DEFAULT_LIBRARY.to_string());
+        libraries
+    }
+}
+
+/// Login of whoever runs kd, recorded with changes and reviews
+pub fn get_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn is_valid_library_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::Config;
+
+    #[test]
+    fn should_keep_named_libraries_apart() {
+        let data_dir = PathBuf::from("/tmp/kd-data");
+        let config = Config::resolve(Some(data_dir.clone()), None, None);
+        assert_eq!(config.get_data_dir(), data_dir);
+
+        let config = Config::resolve(Some(data_dir.clone()), None, Some("team".to_string()));
+        assert_eq!(config.get_data_dir(), data_dir.join("libraries/team"));
+
+        let config = Config::resolve(Some(data_dir), None, Some("../escape".to_string()));
+        assert!(config.init().is_err());
     }
 }
@@ -0,0 +1,187 @@
+use inquire::{Confirm, CustomType, Text};
+use kd::{
+    models::{actor::Actor, show_collection::ShowCollection},
+    Error,
+};
+
+use super::{
+    actor_suggestor, character_suggestor, not_found, show_suggestor,
+    views::{ActorInfo, ActorView, CharacterView, Done},
+    Context, ControllerResult, MoneyFields,
+};
+
+pub fn add_actor_controller(
+    ctx: &Context,
+    name: Option<String>,
+    birth_year: Option<i64>,
+    fee: MoneyFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let name = ctx.resolve(name, "--name", || Text::new("Actor's name:").prompt())?;
+    let birth_year = ctx.resolve(birth_year, "--birth-year", || {
+        CustomType::new("Actor's birth year:")
+            .with_error_message("Please enter a valid year")
+            .prompt()
+    })?;
+
+    let mut actor = Actor::new(name, birth_year);
+    fee.apply(&mut actor.fee)?;
+
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {actor}").as_str())
+            .with_default(true)
+            .with_help_message("Will save if correct"),
+    )? {
+        let view = ActorView::from(show_collection.add_actor(actor)?);
+        show_collection.save()?;
+        ctx.print(&Done::new("Added new actor", view))?;
+    }
+    Ok(())
+}
+
+pub fn update_actor_controller(
+    ctx: &Context,
+    old_name: Option<String>,
+    new_name: Option<String>,
+    birth_year: Option<i64>,
+    fee: MoneyFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let old_name = ctx.resolve(old_name, "--old-name", || {
+        Text::new("Actor's name:")
+            .with_suggester(&|input: &str| {
+                actor_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    let old_actor = match show_collection.get_actor_by_name(&old_name) {
+        Some(actor) => actor,
+        None => {
+            let suggestions =
+                actor_suggestor(&show_collection, &old_name, ctx.settings.suggestions);
+            return Err(not_found("actor", &old_name, suggestions));
+        }
+    };
+    let new_name = ctx.resolve_or(new_name, old_actor.name.clone(), || {
+        Text::new("Actor's new name:")
+            .with_initial_value(&old_actor.name)
+            .prompt()
+    })?;
+    let birth_year = ctx.resolve_or(birth_year, old_actor.birth_year, || {
+        CustomType::new("Actor's birth year:")
+            .with_default((old_actor.birth_year, &|input| input.to_string()))
+            .with_error_message("Please enter a valid year")
+            .prompt()
+    })?;
+    let mut actor = Actor::new(new_name, birth_year);
+    actor.fee = old_actor.fee.clone();
+    fee.apply(&mut actor.fee)?;
+
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {actor}").as_str())
+            .with_default(true)
+            .with_help_message("Will update if correct"),
+    )? {
+        let view = match show_collection.update_actor(&old_name, actor) {
+            Ok(actor) => ActorView::from(actor),
+            Err(e) => return Err(e.context("Unable to update actor")),
+        };
+        show_collection.save()?;
+        ctx.print(&Done::new("Updated actor", view))?;
+    }
+    Ok(())
+}
+
+pub fn cast_actor_controller(
+    ctx: &Context,
+    name: Option<String>,
+    show_name: Option<String>,
+    character_name: Option<String>,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let name = ctx.resolve(name, "--name", || {
+        Text::new("Actor's name:")
+            .with_suggester(&|input: &str| {
+                actor_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    if show_collection.get_actor_by_name(&name).is_none() {
+        let suggestions = actor_suggestor(&show_collection, &name, ctx.settings.suggestions);
+        return Err(not_found("actor", &name, suggestions));
+    }
+    let show_name = ctx.resolve(show_name, "--show-name", || {
+        Text::new("Show's title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    let show = match show_collection.get_show_by_name(&show_name) {
+        Some(show) => show,
+        None => {
+            let suggestions =
+                show_suggestor(&show_collection, &show_name, ctx.settings.suggestions);
+            return Err(not_found("show", &show_name, suggestions));
+        }
+    };
+    let character_name = ctx.resolve(character_name, "--character-name", || {
+        Text::new("Character name:")
+            .with_suggester(&|input: &str| {
+                character_suggestor(show, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    if show.get_character_by_name(&character_name).is_none() {
+        let suggestions = character_suggestor(show, &character_name, ctx.settings.suggestions);
+        return Err(not_found("character", &character_name, suggestions));
+    }
+
+    if ctx.confirm(
+        Confirm::new(
+            format!("Does this info look correct: {name} plays {character_name} in {show_name}")
+                .as_str(),
+        )
+        .with_default(true)
+        .with_help_message("Will save if correct"),
+    )? {
+        if let Err(e) = show_collection.cast(&show_name, &character_name, &name) {
+            return Err(e.context("Unable to cast actor"));
+        }
+        show_collection.save()?;
+        let show = show_collection
+            .get_show_by_name(&show_name)
+            .ok_or_else(|| Error::not_found("show", show_name.as_str()))?;
+        let character = show
+            .get_character_by_name(&character_name)
+            .ok_or_else(|| Error::not_found("character", character_name.as_str()))?;
+        ctx.print(&Done::new(
+            "Cast",
+            CharacterView::new(show, character, &show_collection, ctx.settings.names),
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn display_actor_info(ctx: &Context, name: Option<String>) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+
+    // Get name if not provided from command arguments
+    let name = ctx.resolve(name, "--name", || {
+        Text::new("Actor name")
+            .with_suggester(&|input: &str| {
+                actor_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    match show_collection.get_actor_by_name(&name) {
+        Some(actor) => ctx.print(&ActorInfo::new(actor, &show_collection, ctx.settings.names)),
+        None => {
+            let suggestions = actor_suggestor(&show_collection, &name, ctx.settings.suggestions);
+            Err(not_found("actor", &name, suggestions))
+        }
+    }
+}
@@ -1,164 +1,259 @@
-use inquire::{Confirm, CustomUserError, Text};
-use kd::models::{character::Character, show_collection::ShowCollection};
+use inquire::{Confirm, Text};
+use kd::{
+    models::{
+        character::Character,
+        query::{CharacterSort, Filter},
+        show_collection::ShowCollection,
+    },
+    Error,
+};
 
-use super::show_suggestor;
+use crate::output::Render;
+
+use super::{
+    character_suggestor, not_found, show_suggestor,
+    views::{CharacterView, DeletedCharacter, Done, RelationshipView},
+    Context, ControllerResult, NameFields,
+};
 
 pub fn add_character_controller(
+    ctx: &Context,
+    show_name: Option<String>,
     name: Option<String>,
     role: Option<String>,
     gender: Option<String>,
-) {
-    let mut show_collection = match ShowCollection::load() {
-        Ok(show_collection) => show_collection,
-        Err(e) => return eprintln!("Unable to load shows: {e}"),
-    };
-    let show = Text::new("Show's title:")
-        .with_suggester(&|input: &str| show_suggestor(&show_collection, input))
-        .prompt()
-        .unwrap();
-    let name = match name {
-        Some(name) => name,
-        None => inquire::Text::new("Character name:").prompt().unwrap(),
-    };
-    let role = match role {
-        Some(role) => role,
-        None => {
-            let options = vec!["protagonist", "antagonist", "comic-relief"];
-            inquire::Select::new("Role:", options)
-                .prompt()
-                .unwrap()
-                .to_string()
-        }
-    };
-    let gender = match gender {
-        Some(gender) => gender,
-        None => {
-            let options = vec!["female", "male", "other"];
-            inquire::Select::new("Gender:", options)
-                .prompt()
-                .unwrap()
-                .to_string()
-        }
-    };
+    names: NameFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show = ctx.resolve(show_name, "--show-name", || {
+        Text::new("Show's title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    if show_collection.get_show_by_name(&show).is_none() {
+        let suggestions = show_suggestor(&show_collection, &show, ctx.settings.suggestions);
+        return Err(not_found("show", &show, suggestions));
+    }
+    let name = ctx.resolve(name, "--name", || {
+        inquire::Text::new("Character name:").prompt()
+    })?;
+    let role = ctx.resolve(role, "--role", || {
+        let options = ctx.settings.roles.clone();
+        inquire::Select::new("Role:", options).prompt()
+    })?;
+    let gender = ctx.resolve(gender, "--gender", || {
+        let options = ctx.settings.genders.clone();
+        inquire::Select::new("Gender:", options).prompt()
+    })?;
 
-    let character = Character::new(name, role, gender);
+    let mut character = Character::new(name.clone(), role, gender);
+    names.apply(&mut character.names);
 
-    if let Ok(true) = Confirm::new(format!("Does this info look correct: {character}").as_str())
-        .with_default(true)
-        .with_help_message("Will save if correct")
-        .prompt()
-    {
-        let character = match show_collection.add_character(show.as_str(), character) {
-            Ok(character) => character,
-            Err(e) => return eprintln!("{e}"),
-        };
-        let character_string = character.to_string();
-        match show_collection.save() {
-            Ok(_) => println!("Added new character: {character_string}"),
-            Err(e) => eprintln!("{e}"),
-        }
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {character}").as_str())
+            .with_default(true)
+            .with_help_message("Will save if correct"),
+    )? {
+        show_collection.add_character(show.as_str(), character)?;
+        show_collection.save()?;
+        ctx.print(&Done::new(
+            "Added new character",
+            character_view(ctx, &show_collection, &show, &name)?,
+        ))?;
     }
+    Ok(())
 }
 
 pub fn update_character_controller(
+    ctx: &Context,
+    show_name: Option<String>,
     old_name: Option<String>,
     new_name: Option<String>,
     role: Option<String>,
     gender: Option<String>,
-) {
-    let mut show_collection = match ShowCollection::load() {
-        Ok(show_collection) => show_collection,
-        Err(e) => return eprintln!("Unable to load shows: {e}"),
-    };
-    let show_name = Text::new("Show's title:")
-        .with_suggester(&|input: &str| show_suggestor(&show_collection, input))
-        .prompt()
-        .unwrap();
+    names: NameFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = ctx.resolve(show_name, "--show-name", || {
+        Text::new("Show's title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
     let show = match show_collection.get_show_by_name(&show_name) {
         Some(show) => show,
-        None => return eprintln!("Unable to find show"),
+        None => {
+            let suggestions =
+                show_suggestor(&show_collection, &show_name, ctx.settings.suggestions);
+            return Err(not_found("show", &show_name, suggestions));
+        }
     };
-    let old_name = match old_name {
-        Some(name) => name,
-        None => inquire::Text::new("Character's old name:")
-            .with_suggester(&|input: &str| character_suggestor(&show, input))
+    let old_name = ctx.resolve(old_name, "--old-name", || {
+        inquire::Text::new("Character's old name:")
+            .with_suggester(&|input: &str| {
+                character_suggestor(show, input, ctx.settings.suggestions)
+            })
             .prompt()
-            .unwrap(),
-    };
+    })?;
     let character = match show.get_character_by_name(&old_name) {
         Some(c) => c,
-        None => return eprintln!("Unable to find character"),
+        None => {
+            let suggestions = character_suggestor(show, &old_name, ctx.settings.suggestions);
+            return Err(not_found("character", &old_name, suggestions));
+        }
     };
-    let new_name = match new_name {
-        Some(name) => name,
-        None => inquire::Text::new("Character's new name:")
-            .with_initial_value(&old_name)
+    // The character may have been looked up by one of its other names
+    let new_name = ctx.resolve_or(new_name, character.name.clone(), || {
+        inquire::Text::new("Character's new name:")
+            .with_initial_value(&character.name)
             .prompt()
-            .unwrap(),
-    };
-    let role = match role {
-        Some(role) => role,
+    })?;
+    let role = ctx.resolve_or(role, character.role.clone(), || {
+        let options = ctx.settings.roles.clone();
+        let selected_index = options
+            .iter()
+            .position(|option| *option == character.role)
+            .unwrap_or(0);
+        inquire::Select::new("Role:", options)
+            .with_starting_cursor(selected_index)
+            .with_vim_mode(true)
+            .prompt()
+    })?;
+    let gender = ctx.resolve_or(gender, character.gender.clone(), || {
+        let options = ctx.settings.genders.clone();
+        let selected_index = options
+            .iter()
+            .position(|option| *option == character.gender)
+            .unwrap_or(0);
+        inquire::Select::new("Gender:", options)
+            .with_starting_cursor(selected_index)
+            .with_vim_mode(true)
+            .prompt()
+    })?;
+
+    let mut updated = Character::new(new_name.clone(), role, gender);
+    updated.names = character.names.clone();
+    names.apply(&mut updated.names);
+    let character = updated;
+
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {character}").as_str())
+            .with_default(true)
+            .with_help_message("Will save if correct"),
+    )? {
+        show_collection.update_character(&show_name, &old_name, character)?;
+        show_collection.save()?;
+        ctx.print(&Done::new(
+            "Updated character",
+            character_view(ctx, &show_collection, &show_name, &new_name)?,
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn delete_character_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    name: Option<String>,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = ctx.resolve(show_name, "--show-name", || {
+        Text::new("Show's title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    let show = match show_collection.get_show_by_name(&show_name) {
+        Some(show) => show,
         None => {
-            let options = vec!["protagonist", "antagonist", "comic-relief"];
-            let selected_index = options
-                .iter()
-                .position(|&option| option == character.role)
-                .unwrap_or(0);
-            inquire::Select::new("Role:", options)
-                .with_starting_cursor(selected_index)
-                .with_vim_mode(true)
-                .prompt()
-                .unwrap()
-                .to_string()
+            let suggestions =
+                show_suggestor(&show_collection, &show_name, ctx.settings.suggestions);
+            return Err(not_found("show", &show_name, suggestions));
         }
     };
-    let gender = match gender {
-        Some(gender) => gender,
+    let name = ctx.resolve(name, "--name", || {
+        inquire::Text::new("Character name:")
+            .with_suggester(&|input: &str| {
+                character_suggestor(show, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    let character = match show.get_character_by_name(&name) {
+        Some(c) => c,
         None => {
-            let options = vec!["female", "male", "other"];
-            let selected_index = options
-                .iter()
-                .position(|&option| option == character.gender)
-                .unwrap_or(0);
-            inquire::Select::new("Gender:", options)
-                .with_starting_cursor(selected_index)
-                .with_vim_mode(true)
-                .prompt()
-                .unwrap()
-                .to_string()
+            let suggestions = character_suggestor(show, &name, ctx.settings.suggestions);
+            return Err(not_found("character", &name, suggestions));
         }
     };
 
-    let character = Character::new(new_name, role, gender);
-
-    if let Ok(true) = Confirm::new(format!("Does this info look correct: {character}").as_str())
-        .with_default(true)
-        .with_help_message("Will save if correct")
-        .prompt()
-    {
-        let character = match show_collection.update_character(&show_name, &old_name, character) {
-            Ok(character) => character,
-            Err(e) => return eprintln!("{e}"),
-        };
-        let character_string = character.to_string();
-        match show_collection.save() {
-            Ok(_) => println!("Updated character: {character_string}"),
-            Err(e) => eprintln!("{e}"),
+    // List the relationships that go away with the character
+    let relationships: Vec<RelationshipView> = show
+        .get_character_relationships(&character.id)
+        .into_iter()
+        .map(|r| RelationshipView::new(show, r, ctx.settings.names))
+        .collect();
+    if !relationships.is_empty() {
+        eprintln!("Deleting {} will also delete:", character.name);
+        for relationship in relationships.iter() {
+            eprintln!("\t- {}", relationship.text());
         }
     }
+    let deleted = DeletedCharacter {
+        character: CharacterView::new(show, character, &show_collection, ctx.settings.names),
+        relationships,
+    };
+
+    if ctx.confirm(
+        Confirm::new(format!("Delete character: {character}?").as_str())
+            .with_default(false)
+            .with_help_message("Will delete if confirmed"),
+    )? {
+        show_collection.remove_character(&show_name, &name)?;
+        show_collection.save()?;
+        ctx.print(&Done::new("Deleted character", deleted))?;
+    }
+    Ok(())
 }
 
-fn character_suggestor(
-    show: &&kd::models::show::Show,
-    input: &str,
-) -> Result<Vec<String>, CustomUserError> {
-    let character_names: Vec<&str> = show.characters.iter().map(|c| c.name.as_str()).collect();
-    let input = input.to_lowercase();
+pub fn list_characters_controller(
+    ctx: &Context,
+    filter: Filter,
+    sort: CharacterSort,
+) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+    let characters: Vec<CharacterView> = show_collection
+        .list_characters(&filter, sort)
+        .into_iter()
+        .map(|(show, character)| {
+            CharacterView::new(show, character, &show_collection, ctx.settings.names)
+        })
+        .collect();
+    ctx.print_list(characters, "No characters found")
+}
 
-    Ok(character_names
-        .iter()
-        .filter(|&p| p.to_lowercase().contains(&input))
-        .take(5)
-        .map(|p| String::from(*p))
-        .collect())
+fn character_view(
+    ctx: &Context,
+    show_collection: &ShowCollection,
+    show_name: &str,
+    name: &str,
+) -> Result<CharacterView, Error> {
+    let show = show_collection
+        .get_show_by_name(show_name)
+        .ok_or_else(|| Error::not_found("show", show_name))?;
+    let character = show
+        .get_character_by_name(name)
+        .ok_or_else(|| Error::not_found("character", name))?;
+    Ok(CharacterView::new(
+        show,
+        character,
+        show_collection,
+        ctx.settings.names,
+    ))
 }
@@ -0,0 +1,84 @@
+use std::process::Command;
+
+use kd::{
+    config::Config,
+    settings::{self, Settings},
+    Error,
+};
+
+use crate::output::OutputFormat;
+
+use super::{
+    views::{Done, SettingView},
+    Context, ControllerResult,
+};
+
+pub fn get_controller(ctx: &Context, key: String) -> ControllerResult {
+    let value = ctx.settings.get(&key)?;
+    ctx.print(&SettingView { key, value })
+}
+
+pub fn set_controller(
+    ctx: &Context,
+    config: &Config,
+    key: String,
+    value: String,
+) -> ControllerResult {
+    if key == "format" {
+        value.parse::<OutputFormat>()?;
+    }
+    // Start from the file rather than ctx.settings, which --format may not match
+    let mut settings = Settings::load(config.get_config_dir())?;
+    settings.set(&key, &value)?;
+    settings
+        .save(config.get_config_dir())
+        .map_err(|e| e.context("Unable to save settings"))?;
+    let value = settings.get(&key)?;
+    ctx.print(&Done::new("Set", SettingView { key, value }))
+}
+
+pub fn list_controller(ctx: &Context) -> ControllerResult {
+    let mut views = Vec::new();
+    for key in settings::KEYS {
+        views.push(SettingView {
+            key: key.to_string(),
+            value: ctx.settings.get(key)?,
+        });
+    }
+    ctx.print(&views)
+}
+
+/// Opens config.toml in the `editor` setting, falling back to `$EDITOR` and then vi
+pub fn edit_controller(ctx: &Context, config: &Config) -> ControllerResult {
+    if ctx.no_input {
+        return Err(Error::Validation(
+            "Editing needs input, use kd config set instead".to_string(),
+        ));
+    }
+    let path = config.get_config_dir().join(settings::FILE_NAME);
+    if !path.exists() {
+        ctx.settings.save(config.get_config_dir())?;
+    }
+    let editor = ctx
+        .settings
+        .editor
+        .clone()
+        .or_else(|| std::env::var("EDITOR").ok())
+        .unwrap_or_else(|| "vi".to_string());
+
+    // The editor setting may carry arguments, e.g. "code --wait"
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| Error::Validation("The editor setting is empty".to_string()))?;
+    let status = Command::new(program)
+        .args(parts)
+        .arg(&path)
+        .status()
+        .map_err(|e| Error::from(e).context(format!("Unable to run {editor}")))?;
+    if !status.success() {
+        return Err(Error::Io(format!("{editor} exited with {status}")));
+    }
+    Settings::load(config.get_config_dir())?;
+    Ok(())
+}
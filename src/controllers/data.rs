@@ -0,0 +1,121 @@
+use inquire::Select;
+use kd::{
+    models::{show::Show, show_collection::ShowCollection},
+    storage::Backend,
+    Error,
+};
+
+use crate::output::Render;
+
+use super::{
+    prompt_error,
+    views::{ConvertView, MigrationView, RelationshipView, RepairReport},
+    Context, ControllerResult,
+};
+
+const DELETE_OPTION: &str = "<delete relationship>";
+const SKIP_OPTION: &str = "<skip>";
+
+/// Finds relationships pointing at characters that no longer exist and lets the user
+/// reattach them to a character of the show or delete them
+pub fn repair_controller(ctx: &Context, dry_run: bool, delete_orphans: bool) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    if ctx.no_input && !dry_run && !delete_orphans {
+        return Err(Error::Validation(
+            "Repairing needs input, pass --dry-run or --delete-orphans".to_string(),
+        ));
+    }
+
+    let mut show_ids: Vec<String> = show_collection.shows.keys().cloned().collect();
+    show_ids.sort();
+    let mut report = RepairReport {
+        orphaned: Vec::new(),
+        repaired: 0,
+        dry_run,
+    };
+    for show_id in show_ids {
+        let show = show_collection.shows.get_mut(&show_id).unwrap();
+        let mut keys: Vec<String> = show
+            .get_orphaned_relationships()
+            .iter()
+            .map(|r| r.id.clone())
+            .collect();
+        keys.sort();
+        for key in keys {
+            let orphan = RelationshipView::new(show, &show.relationships[&key], ctx.settings.names);
+            if delete_orphans && !dry_run {
+                show.remove_relationship(&key);
+                report.repaired += 1;
+            } else if !dry_run {
+                eprintln!("{}: {}", orphan.show, orphan.text());
+                if repair_relationship(show, &key)? {
+                    report.repaired += 1;
+                }
+            }
+            report.orphaned.push(orphan);
+        }
+    }
+
+    if report.repaired > 0 {
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+    }
+    ctx.print(&report)
+}
+
+/// Upgrades the data file to the current schema version
+pub fn migrate_controller(ctx: &Context, dry_run: bool) -> ControllerResult {
+    let report =
+        ShowCollection::migrate(dry_run).map_err(|e| e.context("Unable to migrate data file"))?;
+    ctx.print(&MigrationView::new(report, dry_run))
+}
+
+/// Moves the collection to another storage backend
+pub fn convert_controller(ctx: &Context, to: Backend) -> ControllerResult {
+    let report = ShowCollection::convert(to).map_err(|e| e.context("Unable to convert data"))?;
+    ctx.print(&ConvertView::from(report))
+}
+
+/// Prompts for a replacement of each missing end of the relationship. Returns true if the
+/// relationship was changed
+fn repair_relationship(show: &mut Show, key: &str) -> Result<bool, Error> {
+    let relationship = &show.relationships[key];
+    let mut source = relationship.source.clone();
+    let mut target = relationship.target.clone();
+    let mut options: Vec<String> = show.characters.iter().map(|c| c.name.clone()).collect();
+    options.push(DELETE_OPTION.to_string());
+    options.push(SKIP_OPTION.to_string());
+
+    for (label, id) in [("Source", &mut source), ("Target", &mut target)] {
+        if show.get_character_by_id(id).is_some() {
+            continue;
+        }
+        let choice = Select::new(
+            format!("{label} character is missing, replace with:").as_str(),
+            options.clone(),
+        )
+        .prompt()
+        .map_err(prompt_error)?;
+        match choice.as_str() {
+            DELETE_OPTION => {
+                show.remove_relationship(key);
+                return Ok(true);
+            }
+            SKIP_OPTION => return Ok(false),
+            name => match show.get_character_by_name(name) {
+                Some(character) => *id = character.id.clone(),
+                None => return Err(Error::not_found("character", name)),
+            },
+        }
+    }
+
+    match show.reattach_relationship(key, source, target) {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            eprintln!("Unable to repair relationship: {e}");
+            Ok(false)
+        }
+    }
+}
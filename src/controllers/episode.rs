@@ -0,0 +1,314 @@
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use inquire::{validator::Validation, Confirm, CustomType, InquireError, Text};
+use kd::{
+    models::{
+        episode::{Episode, Season},
+        show_collection::ShowCollection,
+    },
+    Error,
+};
+
+use super::{
+    find_show, resolve_season, resolve_show_name,
+    views::{Done, EpisodeView, SeasonView},
+    Context, ControllerResult,
+};
+
+/// Episode details given on the command line, unset ones are prompted for
+pub struct EpisodeFields {
+    pub title: Option<String>,
+    pub air_date: Option<NaiveDate>,
+    pub runtime: Option<u16>,
+    pub synopsis: Option<String>,
+    pub notes: Option<String>,
+}
+
+pub fn add_season_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    number: Option<u16>,
+    title: Option<String>,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "--show-name")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    let next = show.seasons.last().map_or(1, |s| s.number + 1);
+    let number = ctx.resolve_or(number, next, || {
+        CustomType::new("Season number:")
+            .with_default((next, &|input| input.to_string()))
+            .with_error_message("Please enter a valid number")
+            .prompt()
+    })?;
+    let title = ctx.resolve_or(title.map(Some), None, || {
+        prompt_optional::<String>("Season title:", None)
+    })?;
+
+    let season = Season::new(number, non_empty(title));
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {season}").as_str())
+            .with_default(true)
+            .with_help_message("Will save if correct"),
+    )? {
+        show_collection.add_season(&show_name, season)?;
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+        ctx.print(&Done::new(
+            "Added season",
+            season_view(ctx, &show_collection, &show_name, number)?,
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn edit_season_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    number: Option<u16>,
+    title: Option<String>,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "--show-name")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    let number = resolve_season(ctx, show, number)?;
+    let season = show
+        .get_season(number)
+        .ok_or_else(|| Error::not_found("season", number.to_string()))?;
+    let title = ctx.resolve_or(title.map(Some), season.title.clone(), || {
+        prompt_optional("Season title:", season.title.as_ref())
+    })?;
+
+    if ctx.confirm(
+        Confirm::new(format!("Update season {number} of {show_name}?").as_str())
+            .with_default(true)
+            .with_help_message("Will update if correct"),
+    )? {
+        show_collection.update_season(&show_name, number, non_empty(title))?;
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+        ctx.print(&Done::new(
+            "Updated season",
+            season_view(ctx, &show_collection, &show_name, number)?,
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn list_seasons_controller(ctx: &Context, show_name: Option<String>) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "--show-name")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    let seasons: Vec<SeasonView> = show
+        .seasons
+        .iter()
+        .map(|season| SeasonView::new(show, season, ctx.settings.names))
+        .collect();
+    ctx.print_list(seasons, "No seasons found")
+}
+
+pub fn add_episode_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    season: Option<u16>,
+    number: Option<u16>,
+    fields: EpisodeFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "--show-name")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+
+    // Default to the next episode of the latest season
+    let latest = show.seasons.last().map_or(1, |s| s.number);
+    let season = ctx.resolve_or(season, latest, || {
+        CustomType::new("Season number:")
+            .with_default((latest, &|input| input.to_string()))
+            .with_error_message("Please enter a valid number")
+            .prompt()
+    })?;
+    let next = show
+        .get_season(season)
+        .and_then(|s| s.episodes.last())
+        .map_or(1, |e| e.number + 1);
+    let number = ctx.resolve_or(number, next, || {
+        CustomType::new("Episode number:")
+            .with_default((next, &|input| input.to_string()))
+            .with_error_message("Please enter a valid number")
+            .prompt()
+    })?;
+
+    let mut episode = Episode::new(number);
+    fill_episode(ctx, &mut episode, fields)?;
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {episode}").as_str())
+            .with_default(true)
+            .with_help_message("Will save if correct"),
+    )? {
+        show_collection.add_episode(&show_name, season, episode)?;
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+        ctx.print(&Done::new(
+            "Added episode",
+            episode_view(ctx, &show_collection, &show_name, season, number)?,
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn edit_episode_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    season: Option<u16>,
+    number: Option<u16>,
+    fields: EpisodeFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "--show-name")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    let season = resolve_season(ctx, show, season)?;
+    let number = ctx.resolve(number, "--number", || {
+        CustomType::new("Episode number:")
+            .with_error_message("Please enter a valid number")
+            .prompt()
+    })?;
+    let mut episode = show
+        .get_episode(season, number)
+        .ok_or_else(|| Error::not_found("episode", format!("S{season}E{number}")))?
+        .clone();
+
+    fill_episode(ctx, &mut episode, fields)?;
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {episode}").as_str())
+            .with_default(true)
+            .with_help_message("Will update if correct"),
+    )? {
+        show_collection.update_episode(&show_name, season, episode)?;
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+        ctx.print(&Done::new(
+            "Updated episode",
+            episode_view(ctx, &show_collection, &show_name, season, number)?,
+        ))?;
+    }
+    Ok(())
+}
+
+pub fn list_episodes_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    season: Option<u16>,
+) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "--show-name")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    if let Some(season) = season {
+        show.get_season(season)
+            .ok_or_else(|| Error::not_found("season", season.to_string()))?;
+    }
+    let episodes: Vec<EpisodeView> = show
+        .seasons
+        .iter()
+        .filter(|s| season.is_none_or(|season| s.number == season))
+        .flat_map(|s| {
+            s.episodes
+                .iter()
+                .map(|e| EpisodeView::new(show, s, e, ctx.settings.names))
+        })
+        .collect();
+    ctx.print_list(episodes, "No episodes found")
+}
+
+/// Sets the episode's details from the command line, prompting for the rest. Without
+/// input the current values are kept
+fn fill_episode(ctx: &Context, episode: &mut Episode, fields: EpisodeFields) -> ControllerResult {
+    let title = ctx.resolve_or(fields.title.map(Some), episode.title.clone(), || {
+        prompt_optional("Title:", episode.title.as_ref())
+    })?;
+    let air_date = ctx.resolve_or(fields.air_date.map(Some), episode.air_date, || {
+        prompt_optional("Air date (YYYY-MM-DD):", episode.air_date.as_ref())
+    })?;
+    let runtime = ctx.resolve_or(fields.runtime.map(Some), episode.runtime, || {
+        prompt_optional("Runtime in minutes:", episode.runtime.as_ref())
+    })?;
+    let synopsis = ctx.resolve_or(fields.synopsis.map(Some), episode.synopsis.clone(), || {
+        prompt_optional("Synopsis:", episode.synopsis.as_ref())
+    })?;
+    let notes = ctx.resolve_or(fields.notes.map(Some), episode.notes.clone(), || {
+        prompt_optional("Notes:", episode.notes.as_ref())
+    })?;
+
+    episode.title = non_empty(title);
+    episode.air_date = air_date;
+    episode.runtime = runtime;
+    episode.synopsis = non_empty(synopsis);
+    episode.notes = non_empty(notes);
+    Ok(())
+}
+
+/// Prompts for a value that may be left unset by answering with nothing
+fn prompt_optional<T: FromStr + ToString>(
+    message: &str,
+    current: Option<&T>,
+) -> Result<Option<T>, InquireError> {
+    let initial = current.map(T::to_string).unwrap_or_default();
+    let answer = Text::new(message)
+        .with_initial_value(&initial)
+        .with_help_message("Leave empty to unset")
+        .with_validator(&|input: &str| match input.trim().is_empty()
+            || input.trim().parse::<T>().is_ok()
+        {
+            true => Ok(Validation::Valid),
+            false => Ok(Validation::Invalid("Please enter a valid value".into())),
+        })
+        .prompt()?;
+    Ok(answer.trim().parse().ok())
+}
+
+/// Empty text given on the command line unsets the value
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+fn season_view(
+    ctx: &Context,
+    show_collection: &ShowCollection,
+    show_name: &str,
+    number: u16,
+) -> Result<SeasonView, Error> {
+    let show = show_collection
+        .get_show_by_name(show_name)
+        .ok_or_else(|| Error::not_found("show", show_name))?;
+    let season = show
+        .get_season(number)
+        .ok_or_else(|| Error::not_found("season", number.to_string()))?;
+    Ok(SeasonView::new(show, season, ctx.settings.names))
+}
+
+fn episode_view(
+    ctx: &Context,
+    show_collection: &ShowCollection,
+    show_name: &str,
+    season: u16,
+    number: u16,
+) -> Result<EpisodeView, Error> {
+    let show = show_collection
+        .get_show_by_name(show_name)
+        .ok_or_else(|| Error::not_found("show", show_name))?;
+    let season = show
+        .get_season(season)
+        .ok_or_else(|| Error::not_found("season", season.to_string()))?;
+    let episode = season
+        .episodes
+        .iter()
+        .find(|e| e.number == number)
+        .ok_or_else(|| Error::not_found("episode", format!("S{}E{number}", season.number)))?;
+    Ok(EpisodeView::new(show, season, episode, ctx.settings.names))
+}
@@ -0,0 +1,26 @@
+use kd::models::show_collection::ShowCollection;
+
+use super::{
+    views::{Done, EntryView},
+    Context, ControllerResult,
+};
+
+pub fn undo_controller(ctx: &Context) -> ControllerResult {
+    let entry = ShowCollection::undo().map_err(|e| e.context("Unable to undo"))?;
+    ctx.print(&Done::new("Undid", EntryView::from(&entry)))
+}
+
+pub fn redo_controller(ctx: &Context) -> ControllerResult {
+    let entry = ShowCollection::redo().map_err(|e| e.context("Unable to redo"))?;
+    ctx.print(&Done::new("Redid", EntryView::from(&entry)))
+}
+
+pub fn history_controller(ctx: &Context, limit: usize) -> ControllerResult {
+    let entries: Vec<EntryView> = ShowCollection::history()
+        .map_err(|e| e.context("Unable to read history"))?
+        .iter()
+        .take(limit)
+        .map(EntryView::from)
+        .collect();
+    ctx.print_list(entries, "No changes recorded yet")
+}
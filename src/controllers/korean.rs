@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use inquire::Text;
+use kd::{
+    config::Config,
+    korean::{
+        exchange::{
+            parse_currency, AlphaVantage, CachedProvider, ExchangeRateProvider, FixedRates,
+            ALPHA_VANTAGE_URL,
+        },
+        number::parse_amount,
+        romanize::romanize,
+        utils,
+    },
+    models::{money::Money, show_collection::ShowCollection},
+    Error,
+};
+
+use super::{
+    actor_suggestor, find_show, not_found,
+    views::{Conversion, Romanization},
+    Context, ControllerResult,
+};
+
+pub fn romanize_controller(ctx: &Context, text: Option<String>) -> ControllerResult {
+    let hangul = ctx.resolve(text, "<TEXT>", || Text::new("Hangul:").prompt())?;
+    let romanized = romanize(&hangul);
+    ctx.print(&Romanization { hangul, romanized })
+}
+
+/// Alpha Vantage, or the server the `rates_url` setting names, cached in `data_dir` for
+/// the `rates_ttl` setting
+fn cached_rates(ctx: &Context, data_dir: &Path) -> CachedProvider<AlphaVantage> {
+    let base_url = match ctx.settings.rates_url.as_deref() {
+        Some(url) if !url.is_empty() => url,
+        _ => ALPHA_VANTAGE_URL,
+    };
+    CachedProvider::new(
+        AlphaVantage::from_env(base_url),
+        data_dir,
+        Duration::hours(ctx.settings.rates_ttl.into()),
+    )
+}
+
+/// What `kd convert` converts, either an amount or a show's budget or actor's fee
+pub struct ConvertFields {
+    /// Digits or Korean units
+    pub amount: Option<String>,
+    /// Currency of the amount, won when unset
+    pub from: Option<String>,
+    /// Currency to convert to, the currency setting when unset
+    pub to: Option<String>,
+    /// Day to convert at the rate of, the day a budget or fee was quoted when unset
+    pub date: Option<NaiveDate>,
+    /// Units of `to` per unit of `from` to convert at instead of the exchange rate
+    pub rate: Option<f64>,
+    /// Name of the show whose budget is converted
+    pub budget: Option<String>,
+    /// Name of the actor whose fee is converted
+    pub fee: Option<String>,
+}
+
+/// Converts an amount, or a recorded budget or fee, at the rate on its date. Amounts from
+/// a past date converted to USD are also shown adjusted for inflation, the only price index
+/// available being the US one
+pub fn convert_controller(
+    ctx: &Context,
+    config: &Config,
+    fields: ConvertFields,
+) -> ControllerResult {
+    let money = match (fields.budget, fields.fee) {
+        (Some(show_name), _) => {
+            let show_collection =
+                ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+            let show = find_show(ctx, &show_collection, &show_name)?;
+            show.budget
+                .clone()
+                .ok_or_else(|| Error::Validation(format!("{show_name} has no budget")))?
+        }
+        (None, Some(actor_name)) => {
+            let show_collection =
+                ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+            let actor = match show_collection.get_actor_by_name(&actor_name) {
+                Some(actor) => actor,
+                None => {
+                    let suggestions =
+                        actor_suggestor(&show_collection, &actor_name, ctx.settings.suggestions);
+                    return Err(not_found("actor", &actor_name, suggestions));
+                }
+            };
+            actor
+                .fee
+                .clone()
+                .ok_or_else(|| Error::Validation(format!("{actor_name} has no fee")))?
+        }
+        (None, None) => {
+            let amount = ctx.resolve(fields.amount, "<AMOUNT>", || {
+                Text::new("Amount:")
+                    .with_help_message("e.g. 10000000, 1.5억 or 3천만원")
+                    .prompt()
+            })?;
+            let from = fields.from.unwrap_or_else(|| "KRW".to_string());
+            Money::new(parse_amount(&amount)?, &from, None)?
+        }
+    };
+    let date = fields.date.or(money.date);
+    if date.is_some_and(|date| date > Utc::now().date_naive()) {
+        return Err(Error::Validation(
+            "There are no rates for future dates".to_string(),
+        ));
+    }
+    let to = parse_currency(fields.to.as_deref().unwrap_or(&ctx.settings.currency))?;
+
+    let cached = cached_rates(ctx, &config.get_data_dir());
+    let fixed = fields
+        .rate
+        .map(|rate| FixedRates::new().with_rate(&money.currency, &to, rate));
+    let rates: &dyn ExchangeRateProvider = match &fixed {
+        Some(fixed) => fixed,
+        None => &cached,
+    };
+    let (converted, rate) = utils::convert(money.amount, &money.currency, &to, date, rates)
+        .map_err(|e| e.context("Unable to convert"))?;
+    // A given rate stands for the whole conversion, so there's no looking up price indexes
+    let (adjusted, note) = match (date, &fixed) {
+        (Some(date), None) => {
+            match utils::adjust_for_inflation(converted, &to, date.year(), &cached) {
+                Ok(adjusted) => (Some(adjusted), None),
+                // E.g. a currency without a price index, the conversion stands without one
+                Err(e) if matches!(e.root(), Error::Validation(_)) => {
+                    (None, Some(format!("Not adjusted for inflation: {e}")))
+                }
+                Err(e) => return Err(e.context("Unable to adjust for inflation")),
+            }
+        }
+        _ => (None, None),
+    };
+    ctx.print(&Conversion {
+        amount: money.amount,
+        from: money.currency,
+        converted,
+        to,
+        rate: rate.rate,
+        as_of: rate.as_of,
+        date,
+        adjusted,
+        note,
+    })
+}
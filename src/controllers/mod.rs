@@ -1,45 +1,359 @@
-use inquire::CustomUserError;
-use kd::models::{show::Show, show_collection::ShowCollection};
+use chrono::NaiveDate;
+use inquire::{Confirm, CustomType, CustomUserError, InquireError, Text};
+use kd::{
+    korean::{exchange::parse_currency, number::parse_amount, search::match_name},
+    models::{money::Money, names::Names, show::Show, show_collection::ShowCollection},
+    settings::Settings,
+    Error,
+};
 
+use crate::output::{self, Message, OutputFormat, Render};
+
+pub mod actor;
 pub mod character;
+pub mod config;
+pub mod data;
+pub mod episode;
+pub mod history;
+pub mod korean;
+pub mod review;
+pub mod search;
 pub mod show;
+pub mod views;
+pub mod watch;
+
+pub type ControllerResult = Result<(), Error>;
+
+/// Global command line options every controller receives
+pub struct Context {
+    pub format: OutputFormat,
+    /// Answer yes to every confirmation
+    pub assume_yes: bool,
+    /// Never prompt, report missing values as errors instead
+    pub no_input: bool,
+    pub settings: Settings,
+}
+
+impl Context {
+    /// Uses the value given on the command line, or prompts for it when input is allowed
+    pub fn resolve<T>(
+        &self,
+        value: Option<T>,
+        flag: &str,
+        prompt: impl FnOnce() -> Result<T, InquireError>,
+    ) -> Result<T, Error> {
+        match value {
+            Some(value) => Ok(value),
+            None if self.no_input => Err(Error::Validation(format!(
+                "Missing required value, pass {flag}"
+            ))),
+            None => prompt().map_err(prompt_error),
+        }
+    }
+
+    /// Like [`Context::resolve`] but falls back to `default` when input isn't allowed
+    pub fn resolve_or<T>(
+        &self,
+        value: Option<T>,
+        default: T,
+        prompt: impl FnOnce() -> Result<T, InquireError>,
+    ) -> Result<T, Error> {
+        match value {
+            Some(value) => Ok(value),
+            None if self.no_input => Ok(default),
+            None => prompt().map_err(prompt_error),
+        }
+    }
+
+    /// Asks for confirmation unless `--yes` was passed
+    pub fn confirm(&self, confirm: Confirm) -> Result<bool, Error> {
+        if self.assume_yes {
+            return Ok(true);
+        }
+        if self.no_input {
+            return Err(Error::Validation(
+                "Confirmation required, pass --yes".to_string(),
+            ));
+        }
+        confirm.prompt().map_err(prompt_error)
+    }
+
+    pub fn print<T: Render>(&self, value: &T) -> ControllerResult {
+        output::print(self.format, value)
+    }
+
+    /// Prints the items, or the `empty` message when there are none and output is meant for humans
+    pub fn print_list<T: Render>(&self, items: Vec<T>, empty: &str) -> ControllerResult {
+        if items.is_empty() && self.format == OutputFormat::Text {
+            return self.print(&Message::new(empty));
+        }
+        self.print(&items)
+    }
+}
+
+/// Other names of a show or character given on the command line, unset ones are kept
+pub struct NameFields {
+    pub hangul: Option<String>,
+    pub romanized: Option<String>,
+    pub english: Option<Vec<String>>,
+}
+
+impl NameFields {
+    fn apply(self, names: &mut Names) {
+        names.update(self.hangul, self.romanized, self.english);
+    }
+}
+
+/// An amount of money given on the command line, unset parts are kept
+pub struct MoneyFields {
+    /// Digits or Korean units, empty to unset the money
+    pub amount: Option<String>,
+    pub currency: Option<String>,
+    pub date: Option<NaiveDate>,
+}
+
+impl MoneyFields {
+    /// Sets or changes `money`, new amounts are in won unless a currency is given
+    fn apply(self, money: &mut Option<Money>) -> Result<(), Error> {
+        match (self.amount, money.as_mut()) {
+            (Some(amount), _) if amount.trim().is_empty() => *money = None,
+            (Some(amount), old) => {
+                let currency = self
+                    .currency
+                    .or_else(|| old.as_ref().map(|old| old.currency.clone()))
+                    .unwrap_or_else(|| "KRW".to_string());
+                let date = self.date.or_else(|| old.and_then(|old| old.date));
+                *money = Some(Money::new(parse_amount(&amount)?, &currency, date)?);
+            }
+            (None, Some(money)) => {
+                if let Some(currency) = self.currency {
+                    money.currency = parse_currency(&currency)?;
+                }
+                if self.date.is_some() {
+                    money.date = self.date;
+                }
+            }
+            (None, None) if self.currency.is_some() || self.date.is_some() => {
+                return Err(Error::Validation(
+                    "An amount is needed to set a currency or date".to_string(),
+                ))
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Production details of a show given on the command line, unset ones are kept
+pub struct MetadataFields {
+    /// Empty to unset
+    pub network: Option<String>,
+    pub platforms: Option<Vec<String>>,
+    pub genres: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl MetadataFields {
+    /// Sets the details given, lists replace the old ones and empty entries are dropped
+    fn apply(self, show: &mut Show) {
+        let non_empty = |value: String| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        if let Some(network) = self.network {
+            show.network = non_empty(network);
+        }
+        for (values, field) in [
+            (self.platforms, &mut show.platforms),
+            (self.genres, &mut show.genres),
+            (self.tags, &mut show.tags),
+        ] {
+            if let Some(values) = values {
+                *field = values.into_iter().filter_map(non_empty).collect();
+            }
+        }
+    }
+}
+
+/// Show name from the command line, or prompted for with suggestions. `flag` is what
+/// passes it on the command line
+fn resolve_show_name(
+    ctx: &Context,
+    show_collection: &ShowCollection,
+    show_name: Option<String>,
+    flag: &str,
+) -> Result<String, Error> {
+    ctx.resolve(show_name, flag, || {
+        Text::new("Show's title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })
+}
+
+/// The show with the name, suggesting similar names when there is none
+fn find_show<'a>(
+    ctx: &Context,
+    show_collection: &'a ShowCollection,
+    show_name: &str,
+) -> Result<&'a Show, Error> {
+    show_collection.get_show_by_name(show_name).ok_or_else(|| {
+        let suggestions = show_suggestor(show_collection, show_name, ctx.settings.suggestions);
+        not_found("show", show_name, suggestions)
+    })
+}
+
+/// Season number from the command line, or picked from the show's seasons
+fn resolve_season(ctx: &Context, show: &Show, season: Option<u16>) -> Result<u16, Error> {
+    if season.is_none() && show.seasons.len() == 1 {
+        return Ok(show.seasons[0].number);
+    }
+    let latest = show.seasons.last().map_or(1, |s| s.number);
+    ctx.resolve(season, "--season", || {
+        CustomType::new("Season number:")
+            .with_default((latest, &|input| input.to_string()))
+            .with_error_message("Please enter a valid number")
+            .prompt()
+    })
+}
+
+/// Prompt failures as library errors so they carry an exit code
+fn prompt_error(error: InquireError) -> Error {
+    match error {
+        InquireError::OperationCanceled | InquireError::OperationInterrupted => Error::Cancelled,
+        InquireError::IO(error) => Error::from(error),
+        error => Error::Validation(error.to_string()),
+    }
+}
+
+/// A not found error for `name` suggesting what one of the suggestors comes up with
+fn not_found(
+    kind: &'static str,
+    name: &str,
+    suggestions: Result<Vec<String>, CustomUserError>,
+) -> Error {
+    Error::not_found(kind, name).with_suggestions(suggestions.unwrap_or_default())
+}
+
+/// Names matching the input, best matches first, followed by names a typo or two away
+/// from it. Hangul names also match initial consonants and partly typed syllables
+fn suggest<'a>(names: impl Iterator<Item = &'a str>, input: &str, limit: usize) -> Vec<String> {
+    let input = input.to_lowercase();
+    let max_distance = (input.chars().count() / 3).max(1);
+    let mut matched = Vec::new();
+    let mut close = Vec::new();
+    for name in names {
+        match match_name(name, &input) {
+            Some(quality) => matched.push((quality, name.to_string())),
+            None => {
+                let distance = edit_distance(&name.to_lowercase(), &input);
+                if distance <= max_distance {
+                    close.push((distance, name.to_string()));
+                }
+            }
+        }
+    }
+    matched.sort();
+    close.sort();
+    let mut suggestions: Vec<String> = Vec::new();
+    for name in matched
+        .into_iter()
+        .map(|(_, name)| name)
+        .chain(close.into_iter().map(|(_, name)| name))
+    {
+        if !suggestions.contains(&name) {
+            suggestions.push(name);
+        }
+    }
+    suggestions.truncate(limit);
+    suggestions
+}
+
+/// Levenshtein distance between the characters of `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
 
 fn show_suggestor(
     show_collection: &ShowCollection,
     input: &str,
+    limit: usize,
 ) -> Result<Vec<String>, CustomUserError> {
-    let shows: Vec<&str> = show_collection.get_show_names();
-    let input = input.to_lowercase();
-
-    Ok(shows
-        .iter()
-        .filter(|p| p.to_lowercase().contains(&input))
-        .take(5)
-        .map(|p| String::from(*p))
-        .collect())
+    Ok(suggest(
+        show_collection.get_show_names().into_iter(),
+        input,
+        limit,
+    ))
 }
 
-fn character_suggestor(show: &Show, input: &str) -> Result<Vec<String>, CustomUserError> {
-    let characters: Vec<&str> = show.characters.iter().map(|c| c.name.as_str()).collect();
-    let input = input.to_lowercase();
+fn actor_suggestor(
+    show_collection: &ShowCollection,
+    input: &str,
+    limit: usize,
+) -> Result<Vec<String>, CustomUserError> {
+    Ok(suggest(
+        show_collection.get_actor_names().into_iter(),
+        input,
+        limit,
+    ))
+}
 
-    Ok(characters
+fn character_suggestor(
+    show: &Show,
+    input: &str,
+    limit: usize,
+) -> Result<Vec<String>, CustomUserError> {
+    let names = show
+        .characters
         .iter()
-        .filter(|p| p.to_lowercase().contains(&input))
-        .take(5)
-        .map(|p| String::from(*p))
-        .collect())
+        .flat_map(|c| std::iter::once(c.name.as_str()).chain(c.names.iter()));
+    Ok(suggest(names, input, limit))
 }
 
-fn relationship_suggestor(show: &Show, input: &str) -> Result<Vec<String>, CustomUserError> {
-    let input = input.to_lowercase();
-    let relationships = show
+fn relationship_suggestor(
+    show: &Show,
+    input: &str,
+    limit: usize,
+) -> Result<Vec<String>, CustomUserError> {
+    let mut kinds: Vec<&str> = show
         .relationships
         .values()
-        .filter(|&p| p.kind.to_lowercase().contains(&input))
-        .take(5)
-        .map(|r| r.kind.clone())
+        .map(|r| r.kind.as_str())
         .collect();
+    kinds.sort_unstable();
+    kinds.dedup();
+    Ok(suggest(kinds.into_iter(), input, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::suggest;
+
+    #[test]
+    fn should_suggest_close_names() {
+        let names = ["Goblin", "Crash Landing on You", "Vincenzo"];
+        assert_eq!(
+            suggest(names.into_iter(), "crash", 5),
+            vec!["Crash Landing on You"]
+        );
+        assert_eq!(suggest(names.into_iter(), "Gobln", 5), vec!["Goblin"]);
+        assert_eq!(suggest(names.into_iter(), "Vinzenco", 5), vec!["Vincenzo"]);
+        assert!(suggest(names.into_iter(), "Signal", 5).is_empty());
+        assert_eq!(suggest(names.into_iter(), "", 2).len(), 2);
 
-    Ok(relationships)
+        let names = ["사랑의 불시착", "도깨비", "아이리스"];
+        assert_eq!(suggest(names.into_iter(), "ㄷㄲ", 5), vec!["도깨비"]);
+        assert_eq!(
+            suggest(names.into_iter(), "ㅇ", 5),
+            vec!["아이리스", "사랑의 불시착"]
+        );
+    }
 }
@@ -0,0 +1,159 @@
+use inquire::{CustomType, Text};
+use kd::{
+    config,
+    models::{
+        review::{Reviews, MAX_SCORE},
+        show::Show,
+        show_collection::ShowCollection,
+        watch::EpisodeNumber,
+    },
+    Error,
+};
+
+use super::{
+    find_show, resolve_season, resolve_show_name,
+    views::{Done, RankView, ReviewView},
+    Context, ControllerResult,
+};
+
+pub fn rate_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    score: Option<u8>,
+    season: Option<u16>,
+    episode: Option<u16>,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "<SHOW_NAME>")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    let episode = resolve_episode(ctx, show, season, episode)?;
+    let display_name = show.get_display_name(ctx.settings.names).to_string();
+    let score = ctx.resolve(score, "<SCORE>", || {
+        CustomType::new(format!("Score from 1 to {MAX_SCORE}:").as_str())
+            .with_error_message("Please enter a whole number")
+            .prompt()
+    })?;
+
+    let user = config::get_user();
+    let review = show_collection
+        .rate(&show_name, episode, &user, score)?
+        .clone();
+    show_collection
+        .save()
+        .map_err(|e| e.context("Unable to save show collection"))?;
+    ctx.print(&Done::new(
+        "Rated",
+        ReviewView::new(&display_name, episode, &review),
+    ))
+}
+
+pub fn review_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    text: Option<String>,
+    season: Option<u16>,
+    episode: Option<u16>,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "<SHOW_NAME>")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    let episode = resolve_episode(ctx, show, season, episode)?;
+    let display_name = show.get_display_name(ctx.settings.names).to_string();
+    let user = config::get_user();
+    let current = get_reviews(show, episode)
+        .and_then(|reviews| reviews.get_by_user(&user))
+        .and_then(|review| review.text.clone())
+        .unwrap_or_default();
+    let text = ctx.resolve(text, "--text", || {
+        Text::new("Review:").with_initial_value(&current).prompt()
+    })?;
+
+    let review = show_collection
+        .review(&show_name, episode, &user, text)?
+        .clone();
+    show_collection
+        .save()
+        .map_err(|e| e.context("Unable to save show collection"))?;
+    ctx.print(&Done::new(
+        "Reviewed",
+        ReviewView::new(&display_name, episode, &review),
+    ))
+}
+
+/// Ranks shows by score, or the episodes of one show when it's given
+pub fn rank_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    limit: Option<usize>,
+) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+    let ranked: Vec<RankView> = match show_name {
+        Some(show_name) => find_show(ctx, &show_collection, &show_name)?
+            .rank_episodes()
+            .into_iter()
+            .map(|(number, episode, score)| {
+                let name = match &episode.title {
+                    Some(title) => format!("{number}: {title}"),
+                    None => number.to_string(),
+                };
+                (name, score, episode.reviews.get_rating_count())
+            })
+            .collect::<Vec<_>>(),
+        None => show_collection
+            .rank_shows()
+            .into_iter()
+            .map(|(show, score)| {
+                let name = show.get_display_name(ctx.settings.names).to_string();
+                (name, score, show.reviews.get_rating_count())
+            })
+            .collect(),
+    }
+    .into_iter()
+    .take(limit.unwrap_or(usize::MAX))
+    .enumerate()
+    .map(|(index, (name, score, ratings))| RankView {
+        rank: index + 1,
+        name,
+        score,
+        ratings,
+    })
+    .collect();
+    ctx.print_list(ranked, "Nothing has been rated yet")
+}
+
+/// The episode being rated or reviewed, None for the show itself
+fn resolve_episode(
+    ctx: &Context,
+    show: &Show,
+    season: Option<u16>,
+    episode: Option<u16>,
+) -> Result<Option<EpisodeNumber>, Error> {
+    let episode = match episode {
+        Some(episode) => episode,
+        None if season.is_some() => {
+            return Err(Error::Validation(
+                "Pass --episode along with --season".to_string(),
+            ))
+        }
+        None => return Ok(None),
+    };
+    let number = EpisodeNumber {
+        season: resolve_season(ctx, show, season)?,
+        episode,
+    };
+    match show.get_episode(number.season, number.episode) {
+        Some(_) => Ok(Some(number)),
+        None => Err(Error::not_found("episode", number.to_string())),
+    }
+}
+
+fn get_reviews(show: &Show, episode: Option<EpisodeNumber>) -> Option<&Reviews> {
+    match episode {
+        Some(number) => show
+            .get_episode(number.season, number.episode)
+            .map(|episode| &episode.reviews),
+        None => Some(&show.reviews),
+    }
+}
@@ -0,0 +1,13 @@
+use kd::models::{query::Filter, show_collection::ShowCollection};
+
+use super::{views::SearchHit, Context, ControllerResult};
+
+pub fn search_controller(ctx: &Context, term: String, filter: Filter) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+    let results: Vec<SearchHit> = show_collection
+        .search(&term, &filter)
+        .iter()
+        .map(|result| SearchHit::new(result, ctx.settings.names))
+        .collect();
+    ctx.print_list(results, &format!("Nothing matches \"{term}\""))
+}
@@ -1,206 +1,388 @@
+use chrono::Duration;
 use inquire::{Confirm, CustomType, Text};
 use kd::{
-    models::{show::Show, show_collection::ShowCollection},
-    DisplayMoreInfo,
+    config::Config,
+    korean::{
+        exchange::{CacheOnly, CachedProvider},
+        utils,
+    },
+    models::{
+        money::Money,
+        query::{Filter, ShowSort},
+        show::Show,
+        show_collection::ShowCollection,
+    },
+    Error,
 };
 
-use super::{character_suggestor, relationship_suggestor, show_suggestor};
+use crate::output::Message;
 
-pub fn add_show_controller(name: Option<String>, release_year: Option<i16>) {
-    let validated_name: String;
-    let validated_release_year: i16;
-    let mut show_collection = match ShowCollection::load() {
-        Ok(show_collection) => show_collection,
-        Err(e) => return eprintln!("Unable to load shows: {e}"),
-    };
-    if let Some(name) = name {
-        validated_name = name;
-    } else {
-        validated_name = Text::new("Show's title:")
-            .with_suggester(&|input: &str| show_suggestor(&show_collection, input))
+use super::{
+    character_suggestor, not_found, relationship_suggestor, show_suggestor,
+    views::{Done, RelationshipView, ShowInfo, ShowSummary},
+    Context, ControllerResult, MetadataFields, MoneyFields, NameFields,
+};
+
+pub fn add_show_controller(
+    ctx: &Context,
+    name: Option<String>,
+    release_year: Option<i16>,
+    names: NameFields,
+    budget: MoneyFields,
+    metadata: MetadataFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let validated_name = ctx.resolve(name, "--name", || {
+        Text::new("Show's title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
             .prompt()
-            .unwrap();
-    }
-    if let Some(release_year) = release_year {
-        validated_release_year = release_year;
-    } else {
-        validated_release_year = CustomType::new("Show's release year:")
+    })?;
+    let validated_release_year = ctx.resolve(release_year, "--release-year", || {
+        CustomType::new("Show's release year:")
             .with_error_message("Please enter a valid year")
             .prompt()
-            .unwrap();
-    }
-    let show = Show::new(validated_name, validated_release_year);
-    if let Ok(true) = Confirm::new(format!("Does this info look correct: {show}").as_str())
-        .with_default(true)
-        .with_help_message("Will save if correct")
-        .prompt()
-    {
-        show_collection.add(show);
-
-        match show_collection.save() {
-            Ok(_) => println!("Saved show"),
-            Err(e) => return eprintln!("Unable to save show collection: {e}"),
-        }
+    })?;
+    let mut show = Show::new(validated_name, validated_release_year);
+    names.apply(&mut show.names);
+    budget.apply(&mut show.budget)?;
+    metadata.apply(&mut show);
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {show}").as_str())
+            .with_default(true)
+            .with_help_message("Will save if correct"),
+    )? {
+        let summary = match show_collection.add(show) {
+            Ok(show) => ShowSummary::new(show, ctx.settings.names),
+            Err(e) => return Err(e.context("Unable to add show")),
+        };
+
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+        ctx.print(&Done::new("Saved show", summary))?;
     }
+    Ok(())
 }
 
 pub fn update_show_controller(
+    ctx: &Context,
     old_name: Option<String>,
     new_name: Option<String>,
     release_year: Option<i16>,
-) {
-    let mut show_collection = match ShowCollection::load() {
-        Ok(show_collection) => show_collection,
-        Err(e) => return eprintln!("Unable to load shows: {e}"),
-    };
-    let old_name = match old_name {
-        Some(old_name) => old_name,
-        None => Text::new("Show's old title:")
-            .with_suggester(&|input: &str| show_suggestor(&show_collection, input))
+    names: NameFields,
+    budget: MoneyFields,
+    metadata: MetadataFields,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let old_name = ctx.resolve(old_name, "--old-name", || {
+        Text::new("Show's old title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
             .prompt()
-            .unwrap(),
+    })?;
+    let old_show = match show_collection.get_show_by_name(&old_name) {
+        Some(show) => show,
+        None => {
+            let suggestions = show_suggestor(&show_collection, &old_name, ctx.settings.suggestions);
+            return Err(not_found("show", &old_name, suggestions));
+        }
     };
-    let old_show = show_collection.get_show_by_name(&old_name).unwrap();
-    let new_name = match new_name {
-        Some(new_name) => new_name,
-        None => Text::new("Show's new title:")
-            .with_initial_value(&old_name)
+    // The show may have been looked up by one of its other titles
+    let new_name = ctx.resolve_or(new_name, old_show.name.clone(), || {
+        Text::new("Show's new title:")
+            .with_initial_value(&old_show.name)
             .prompt()
-            .unwrap(),
-    };
-    let release_year = match release_year {
-        Some(release_year) => release_year,
-        None => CustomType::new("Show's release year:")
+    })?;
+    let release_year = ctx.resolve_or(release_year, old_show.release_year, || {
+        CustomType::new("Show's release year:")
             .with_default((old_show.release_year, &|input| input.to_string()))
             .with_error_message("Please enter a valid year")
             .prompt()
-            .unwrap(),
-    };
-    let show = Show::new(new_name, release_year);
-    if let Ok(true) = Confirm::new(format!("Does this info look correct: {show}").as_str())
-        .with_default(true)
-        .with_help_message("Will update if correct")
-        .prompt()
-    {
-        match show_collection.update(&old_name, show) {
-            Ok(_) => match show_collection.save() {
-                Ok(_) => println!("Saved show"),
-                Err(e) => return eprintln!("Unable to save show collection: {e}"),
-            },
-            Err(e) => return eprintln!("Unable to update show: {e}"),
+    })?;
+    let mut show = Show::new(new_name, release_year);
+    show.names = old_show.names.clone();
+    names.apply(&mut show.names);
+    show.budget = old_show.budget.clone();
+    budget.apply(&mut show.budget)?;
+    show.network = old_show.network.clone();
+    show.platforms = old_show.platforms.clone();
+    show.genres = old_show.genres.clone();
+    show.tags = old_show.tags.clone();
+    metadata.apply(&mut show);
+    if ctx.confirm(
+        Confirm::new(format!("Does this info look correct: {show}").as_str())
+            .with_default(true)
+            .with_help_message("Will update if correct"),
+    )? {
+        let summary = match show_collection.update(&old_name, show) {
+            Ok(show) => ShowSummary::new(show, ctx.settings.names),
+            Err(e) => return Err(e.context("Unable to update show")),
+        };
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+        ctx.print(&Done::new("Saved show", summary))?;
+    }
+    Ok(())
+}
+
+pub fn delete_show_controller(ctx: &Context, name: Option<String>) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let name = ctx.resolve(name, "--name", || {
+        Text::new("Show's title:")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
+            .prompt()
+    })?;
+    let show = match show_collection.get_show_by_name(&name) {
+        Some(show) => show,
+        None => {
+            let suggestions = show_suggestor(&show_collection, &name, ctx.settings.suggestions);
+            return Err(not_found("show", &name, suggestions));
         }
+    };
+
+    if ctx.confirm(
+        Confirm::new(
+            format!(
+                "Delete {show} along with its {} character(s) and {} relationship(s)?",
+                show.characters.len(),
+                show.relationships.len()
+            )
+            .as_str(),
+        )
+        .with_default(false)
+        .with_help_message("Will delete if confirmed"),
+    )? {
+        let show = match show_collection.remove(&name) {
+            Ok(show) => show,
+            Err(e) => return Err(e.context("Unable to delete show")),
+        };
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save show collection"))?;
+        ctx.print(&Done::new(
+            "Deleted show",
+            ShowSummary::new(&show, ctx.settings.names),
+        ))?;
     }
+    Ok(())
 }
 
-pub fn display_more_info(name: Option<String>) {
-    let show_collection = ShowCollection::load().unwrap();
+pub fn list_shows_controller(ctx: &Context, filter: Filter, sort: ShowSort) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+    let shows: Vec<ShowSummary> = show_collection
+        .list_shows(&filter, sort)
+        .into_iter()
+        .map(|show| ShowSummary::new(show, ctx.settings.names))
+        .collect();
+    ctx.print_list(shows, "No shows found")
+}
 
+pub fn display_more_info(ctx: &Context, name: Option<String>) -> ControllerResult {
     // Get name if not provided from command arguments
     let name = match name {
         Some(name) => name,
-        None => Text::new("Show name")
-            .with_suggester(&|input: &str| show_suggestor(&show_collection, input))
-            .prompt()
-            .unwrap(),
+        None => {
+            let show_collection =
+                ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+            ctx.resolve(None, "--name", || {
+                Text::new("Show name")
+                    .with_suggester(&|input: &str| {
+                        show_suggestor(&show_collection, input, ctx.settings.suggestions)
+                    })
+                    .prompt()
+            })?
+        }
     };
-    match show_collection
-        .shows
-        .values()
-        .find(|&show| show.get_name() == name.as_str())
-    {
-        Some(show) => println!("{}", show.more_info()),
-        None => eprintln!("Couldn't find show by that name"),
+    let show_collection =
+        ShowCollection::read_show(&name).map_err(|e| e.context("Unable to load shows"))?;
+    match show_collection.get_show_by_name(&name) {
+        Some(show) => {
+            let converted_budget = match &show.budget {
+                Some(budget) => convert_budget(ctx, budget).map_err(|e| e.to_string()),
+                None => Ok(None),
+            };
+            ctx.print(&ShowInfo::new(
+                show,
+                &show_collection,
+                ctx.settings.names,
+                converted_budget,
+            ))
+        }
+        None => {
+            // Only the matching show was read, suggestions need all of them
+            let show_collection =
+                ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+            let suggestions = show_suggestor(&show_collection, &name, ctx.settings.suggestions);
+            Err(not_found("show", &name, suggestions))
+        }
     }
 }
 
 pub fn set_relationship_controller(
+    ctx: &Context,
     show_name: Option<String>,
     source_name: Option<String>,
     target_name: Option<String>,
     relationship_name: Option<String>,
-) {
-    let mut show_collection = match ShowCollection::load() {
-        Ok(show_collection) => show_collection,
-        Err(e) => return eprintln!("Unable to load shows: {e}"),
-    };
-    let show_name = match show_name {
-        Some(show_name) => show_name,
-        None => Text::new("Show name")
-            .with_suggester(&|input: &str| show_suggestor(&show_collection, input))
+    delete: bool,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = ctx.resolve(show_name, "--show-name", || {
+        Text::new("Show name")
+            .with_suggester(&|input: &str| {
+                show_suggestor(&show_collection, input, ctx.settings.suggestions)
+            })
             .prompt()
-            .unwrap(),
+    })?;
+    let show = match show_collection.get_show_by_name(&show_name) {
+        Some(show) => show.id.clone(),
+        None => {
+            let suggestions =
+                show_suggestor(&show_collection, &show_name, ctx.settings.suggestions);
+            return Err(not_found("show", &show_name, suggestions));
+        }
     };
     let show = show_collection
-        .get_show_by_name(&show_name)
-        .unwrap()
-        .id
-        .to_string();
-    let show = show_collection.shows.get_mut(&show).unwrap();
-    let source_name = match source_name {
-        Some(source_name) => source_name,
-        None => Text::new("Source character")
-            .with_suggester(&|input: &str| character_suggestor(&show, input))
+        .shows
+        .get_mut(&show)
+        .ok_or_else(|| Error::not_found("show", show_name.as_str()))?;
+    let source_name = ctx.resolve(source_name, "--source-name", || {
+        Text::new("Source character")
+            .with_suggester(&|input: &str| {
+                character_suggestor(show, input, ctx.settings.suggestions)
+            })
             .prompt()
-            .unwrap(),
-    };
+    })?;
     let source = match show.get_character_by_name(&source_name) {
         Some(s) => s,
-        None => return eprintln!("Source not found"),
+        None => {
+            let suggestions = character_suggestor(show, &source_name, ctx.settings.suggestions);
+            return Err(not_found("character", &source_name, suggestions));
+        }
     };
-    let target_name = match target_name {
-        Some(t) => t,
-        None => Text::new("Target character")
-            .with_suggester(&|input: &str| character_suggestor(&show, input))
+    let target_name = ctx.resolve(target_name, "--target-name", || {
+        Text::new("Target character")
+            .with_suggester(&|input: &str| {
+                character_suggestor(show, input, ctx.settings.suggestions)
+            })
             .prompt()
-            .unwrap(),
-    };
+    })?;
     let target = match show.get_character_by_name(&target_name) {
         Some(t) => t,
-        None => return eprintln!("Target not found"),
+        None => {
+            let suggestions = character_suggestor(show, &target_name, ctx.settings.suggestions);
+            return Err(not_found("character", &target_name, suggestions));
+        }
     };
 
     // Look up possible already-existing relationship
     let rel = show.find_rel(&source.id, &target.id);
 
+    if delete {
+        let rel = match rel {
+            Some(rel) => rel,
+            None => {
+                return Err(Error::not_found(
+                    "relationship",
+                    format!("{source_name} -> {target_name}"),
+                ))
+            }
+        };
+        if !ctx.confirm(
+            Confirm::new(
+                format!(
+                    "Delete relationship: {} is a {} to {}?",
+                    source.name, rel.kind, target.name
+                )
+                .as_str(),
+            )
+            .with_default(false)
+            .with_help_message("Will delete if confirmed"),
+        )? {
+            return ctx.print(&Message::new("Canceling"));
+        }
+        let view = RelationshipView::new(show, rel, ctx.settings.names);
+        let key = rel.id.clone();
+        show.remove_relationship(&key);
+
+        show_collection
+            .save()
+            .map_err(|e| e.context("Unable to save"))?;
+        return ctx.print(&Done::new("Deleted relationship", view));
+    }
+
     let default_kind = match rel {
         Some(rel) => rel.kind.as_str(),
         None => ""
     };
     // let relationship_kinds = vec!["girlfriend", "friend"];
-    let relationship_name = match relationship_name {
-        Some(name) => name,
-        None => Text::new("Relation name:")
-            .with_suggester(&|input: &str| relationship_suggestor(&show, input))
+    let relationship_name = ctx.resolve(relationship_name, "--relationship-name", || {
+        Text::new("Relation name:")
+            .with_suggester(&|input: &str| {
+                relationship_suggestor(show, input, ctx.settings.suggestions)
+            })
             .with_default(default_kind)
             .prompt()
-            .unwrap(),
-    };
+    })?;
     // let kind = Select::new("Relationship type", relationship_kinds)
     //     .prompt()
     //     .unwrap();
 
-    if let Ok(false) | Err(_) = Confirm::new(
-        format!(
-            "Does this info look correct: {} is a {} to {}",
-            source.name, relationship_name, target.name
+    if !ctx.confirm(
+        Confirm::new(
+            format!(
+                "Does this info look correct: {} is a {} to {}",
+                source.name, relationship_name, target.name
+            )
+            .as_str(),
         )
-        .as_str(),
-    )
-    .with_default(true)
-    .with_help_message("Will update if correct")
-    .prompt()
-    {
-        return println!("Canceling");
+        .with_default(true)
+        .with_help_message("Will update if correct"),
+    )? {
+        return ctx.print(&Message::new("Canceling"));
     }
-    if let Err(err) = show.set_relationship(
-        source.id.to_string(),
-        target.id.to_string(),
-        relationship_name.to_string(),
-    ) {
-        return eprintln!("Unable to set relationship: {err}");
+    let source_id = source.id.to_string();
+    let target_id = target.id.to_string();
+    let key = match show.set_relationship(source_id, target_id, relationship_name.to_string()) {
+        Ok(relationship) => relationship.id.clone(),
+        Err(err) => return Err(err.context("Unable to set relationship")),
     };
+    let view = RelationshipView::new(show, &show.relationships[&key], ctx.settings.names);
+
+    show_collection
+        .save()
+        .map_err(|e| e.context("Unable to save"))?;
+    ctx.print(&Done::new("Set relationship", view))
+}
 
-    if let Err(e) = show_collection.save() {
-        return eprintln!("Unable to save: {e}");
+/// The budget in the currency setting at the rate of the day it was quoted, None when it's
+/// already in that currency. Only cached rates are used, so showing info never waits on
+/// the network
+fn convert_budget(ctx: &Context, budget: &Money) -> Result<Option<Money>, Error> {
+    if budget.currency.eq_ignore_ascii_case(&ctx.settings.currency) {
+        return Ok(None);
     }
+    let rates = CachedProvider::new(
+        CacheOnly,
+        &Config::new().get_data_dir(),
+        Duration::hours(ctx.settings.rates_ttl.into()),
+    );
+    let (amount, _) = utils::convert(
+        budget.amount,
+        &budget.currency,
+        &ctx.settings.currency,
+        budget.date,
+        &rates,
+    )?;
+    Money::new(amount, &ctx.settings.currency, None).map(Some)
 }
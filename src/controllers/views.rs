@@ -0,0 +1,1109 @@
+//! Serializable shapes of controller results
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use kd::{
+    korean::utils::{format_currency, get_locale},
+    models::{
+        actor::Actor,
+        character::Character,
+        episode::{format_runtime, Episode, Season},
+        journal::{Entry, EntryKind},
+        migration::MigrationReport,
+        money::Money,
+        names::{NameVariant, Names},
+        query::SearchResult,
+        review::{Review, MAX_SCORE},
+        show::{Relationship, Show, REVIEW_EXCERPT_LENGTH},
+        show_collection::{ConvertReport, ShowCollection},
+        watch::{EpisodeNumber, WatchStatus},
+    },
+};
+use serde::{Serialize, Serializer};
+
+use crate::output::Render;
+
+/// A result prefixed with what was done to it in the text format, e.g. "Saved show: ..."
+pub struct Done<T> {
+    action: &'static str,
+    value: T,
+}
+
+impl<T> Done<T> {
+    pub fn new(action: &'static str, value: T) -> Done<T> {
+        Done { action, value }
+    }
+}
+
+impl<T: Serialize> Serialize for Done<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<T: Render> Render for Done<T> {
+    fn text(&self) -> String {
+        format!("{}: {}", self.action, self.value.text())
+    }
+
+    fn headers() -> Vec<&'static str> {
+        T::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.value.rows()
+    }
+}
+
+#[derive(Serialize)]
+pub struct ShowSummary {
+    pub id: String,
+    pub name: String,
+    pub release_year: i16,
+    pub characters: usize,
+    pub relationships: usize,
+}
+
+impl ShowSummary {
+    pub fn new(show: &Show, names: NameVariant) -> ShowSummary {
+        ShowSummary {
+            id: show.id.clone(),
+            name: show.get_display_name(names).to_string(),
+            release_year: show.release_year,
+            characters: show.characters.len(),
+            relationships: show.relationships.len(),
+        }
+    }
+}
+
+impl Render for ShowSummary {
+    fn text(&self) -> String {
+        format!(
+            "{} - {} ({} characters)",
+            self.name, self.release_year, self.characters
+        )
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Name", "Year", "Characters", "Relationships"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.name.clone(),
+            self.release_year.to_string(),
+            self.characters.to_string(),
+            self.relationships.to_string(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct ShowInfo {
+    pub id: String,
+    pub name: String,
+    /// Hangul, romanized and English titles
+    pub names: Names,
+    pub release_year: i16,
+    pub characters: Vec<CharacterView>,
+    pub relationships: Vec<RelationshipView>,
+    pub episodes: usize,
+    /// Total runtime in minutes
+    pub runtime: u32,
+    /// Average score of the show's ratings
+    pub score: Option<f32>,
+    pub ratings: usize,
+    /// Most recent written review, its text cut to an excerpt
+    pub latest_review: Option<ReviewView>,
+    pub network: Option<String>,
+    pub platforms: Vec<String>,
+    pub genres: Vec<String>,
+    pub tags: Vec<String>,
+    pub budget: Option<Money>,
+    /// Budget in the currency setting, when it's in another one
+    pub converted_budget: Option<Money>,
+    /// Why the budget couldn't be converted to the currency setting
+    pub budget_note: Option<String>,
+    /// None when the show's progress isn't tracked
+    pub watch: Option<WatchView>,
+    #[serde(skip)]
+    text: String,
+}
+
+impl ShowInfo {
+    /// `converted_budget` is the budget in the currency setting, None when it's already in
+    /// it, or why it couldn't be converted
+    pub fn new(
+        show: &Show,
+        show_collection: &ShowCollection,
+        names: NameVariant,
+        converted_budget: Result<Option<Money>, String>,
+    ) -> ShowInfo {
+        let mut relationships: Vec<RelationshipView> = show
+            .relationships
+            .values()
+            .map(|r| RelationshipView::new(show, r, names))
+            .collect();
+        relationships.sort_by(|a, b| a.source.cmp(&b.source).then(a.target.cmp(&b.target)));
+        let (converted_budget, budget_note) = match converted_budget {
+            Ok(converted) => (converted, None),
+            Err(note) => (None, Some(note)),
+        };
+        let note = match (&converted_budget, &budget_note) {
+            (Some(converted), _) => Some(format!("about {converted}")),
+            (None, Some(note)) => Some(format!("not converted: {note}")),
+            (None, None) => None,
+        };
+        ShowInfo {
+            id: show.id.clone(),
+            name: show.get_display_name(names).to_string(),
+            names: show.names.clone(),
+            release_year: show.release_year,
+            characters: show
+                .characters
+                .iter()
+                .map(|c| CharacterView::new(show, c, show_collection, names))
+                .collect(),
+            relationships,
+            episodes: show.get_episode_count(),
+            runtime: show.get_runtime(),
+            score: show.reviews.get_score(),
+            ratings: show.reviews.get_rating_count(),
+            latest_review: show.reviews.get_latest_text().map(|review| ReviewView {
+                text: review.get_excerpt(REVIEW_EXCERPT_LENGTH),
+                ..ReviewView::new(show.get_display_name(names), None, review)
+            }),
+            network: show.network.clone(),
+            platforms: show.platforms.clone(),
+            genres: show.genres.clone(),
+            tags: show.tags.clone(),
+            budget: show.budget.clone(),
+            text: show.more_info_with_cast(&show_collection.actors, names, note.as_deref()),
+            converted_budget,
+            budget_note,
+            watch: WatchView::new(show, names),
+        }
+    }
+}
+
+impl Render for ShowInfo {
+    fn text(&self) -> String {
+        self.text.trim_end().to_string()
+    }
+
+    fn headers() -> Vec<&'static str> {
+        CharacterView::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.characters.iter().flat_map(Render::rows).collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct CharacterView {
+    pub id: String,
+    pub show: String,
+    pub name: String,
+    /// Hangul, romanized and English names
+    pub names: Names,
+    pub role: String,
+    pub gender: String,
+    pub actor: Option<String>,
+}
+
+impl CharacterView {
+    pub fn new(
+        show: &Show,
+        character: &Character,
+        show_collection: &ShowCollection,
+        names: NameVariant,
+    ) -> Self {
+        CharacterView {
+            id: character.id.clone(),
+            show: show.get_display_name(names).to_string(),
+            name: character.get_display_name(names).to_string(),
+            names: character.names.clone(),
+            role: character.role.clone(),
+            gender: character.gender.clone(),
+            actor: character
+                .actor_id
+                .as_ref()
+                .and_then(|id| show_collection.actors.get(id))
+                .map(|actor| actor.name.clone()),
+        }
+    }
+}
+
+impl Render for CharacterView {
+    fn text(&self) -> String {
+        let mut text = format!(
+            "{} ({}) - {} in {}",
+            self.name, self.gender, self.role, self.show
+        );
+        if let Some(actor) = &self.actor {
+            text.push_str(&format!(" (played by {actor})"));
+        }
+        text
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Show", "Name", "Gender", "Role", "Actor"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.show.clone(),
+            self.name.clone(),
+            self.gender.clone(),
+            self.role.clone(),
+            self.actor.clone().unwrap_or_default(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeletedCharacter {
+    #[serde(flatten)]
+    pub character: CharacterView,
+    pub relationships: Vec<RelationshipView>,
+}
+
+impl Render for DeletedCharacter {
+    fn text(&self) -> String {
+        format!(
+            "{} and {} relationship(s)",
+            self.character.text(),
+            self.relationships.len()
+        )
+    }
+
+    fn headers() -> Vec<&'static str> {
+        CharacterView::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.character.rows()
+    }
+}
+
+#[derive(Serialize)]
+pub struct RelationshipView {
+    pub show: String,
+    pub source: String,
+    pub target: String,
+    pub kind: String,
+}
+
+impl RelationshipView {
+    pub fn new(show: &Show, relationship: &Relationship, names: NameVariant) -> Self {
+        let name = |id: &str| {
+            show.get_character_by_id(id)
+                .map_or(id.to_string(), |c| c.get_display_name(names).to_string())
+        };
+        RelationshipView {
+            show: show.get_display_name(names).to_string(),
+            source: name(&relationship.source),
+            target: name(&relationship.target),
+            kind: relationship.kind.clone(),
+        }
+    }
+}
+
+impl Render for RelationshipView {
+    fn text(&self) -> String {
+        format!("{} is a {} to {}", self.source, self.kind, self.target)
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Show", "Source", "Kind", "Target"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.show.clone(),
+            self.source.clone(),
+            self.kind.clone(),
+            self.target.clone(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct SeasonView {
+    pub show: String,
+    pub number: u16,
+    pub title: Option<String>,
+    pub episodes: usize,
+    /// Total runtime in minutes
+    pub runtime: u32,
+}
+
+impl SeasonView {
+    pub fn new(show: &Show, season: &Season, names: NameVariant) -> SeasonView {
+        SeasonView {
+            show: show.get_display_name(names).to_string(),
+            number: season.number,
+            title: season.title.clone(),
+            episodes: season.episodes.len(),
+            runtime: season.get_runtime(),
+        }
+    }
+}
+
+impl Render for SeasonView {
+    fn text(&self) -> String {
+        let mut text = format!("{} season {}", self.show, self.number);
+        if let Some(title) = &self.title {
+            text.push_str(&format!(": {title}"));
+        }
+        text.push_str(&format!(
+            " ({} episodes, {})",
+            self.episodes,
+            format_runtime(self.runtime)
+        ));
+        text
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Show", "Season", "Title", "Episodes", "Runtime"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.show.clone(),
+            self.number.to_string(),
+            self.title.clone().unwrap_or_default(),
+            self.episodes.to_string(),
+            format_runtime(self.runtime),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct EpisodeView {
+    pub id: String,
+    pub show: String,
+    pub season: u16,
+    pub number: u16,
+    pub title: Option<String>,
+    pub air_date: Option<NaiveDate>,
+    /// Length in minutes
+    pub runtime: Option<u16>,
+    pub synopsis: Option<String>,
+    pub notes: Option<String>,
+    /// Average score of the episode's ratings
+    pub score: Option<f32>,
+    #[serde(skip)]
+    text: String,
+}
+
+impl EpisodeView {
+    pub fn new(show: &Show, season: &Season, episode: &Episode, names: NameVariant) -> EpisodeView {
+        let show_name = show.get_display_name(names);
+        EpisodeView {
+            id: episode.id.clone(),
+            show: show_name.to_string(),
+            season: season.number,
+            number: episode.number,
+            title: episode.title.clone(),
+            air_date: episode.air_date,
+            runtime: episode.runtime,
+            synopsis: episode.synopsis.clone(),
+            notes: episode.notes.clone(),
+            score: episode.reviews.get_score(),
+            text: format!("{show_name} season {}, {episode}", season.number),
+        }
+    }
+}
+
+impl Render for EpisodeView {
+    fn text(&self) -> String {
+        let mut text = self.text.clone();
+        if let Some(synopsis) = &self.synopsis {
+            text.push_str(&format!("\n\t{synopsis}"));
+        }
+        if let Some(notes) = &self.notes {
+            text.push_str(&format!("\n\tNotes: {notes}"));
+        }
+        text
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Show", "Season", "Episode", "Title", "Aired", "Runtime"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.show.clone(),
+            self.season.to_string(),
+            self.number.to_string(),
+            self.title.clone().unwrap_or_default(),
+            self.air_date
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+            self.runtime
+                .map(|runtime| format!("{runtime} min"))
+                .unwrap_or_default(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct WatchView {
+    pub show: String,
+    pub status: WatchStatus,
+    pub last_episode: Option<String>,
+    pub watched: usize,
+    pub episodes: usize,
+    /// The episode to watch next, e.g. "S1E4: The Bride"
+    pub next: Option<String>,
+    pub started: Option<NaiveDate>,
+    pub finished: Option<NaiveDate>,
+    pub last_watched: Option<DateTime<Local>>,
+}
+
+impl WatchView {
+    /// None for shows whose progress isn't tracked
+    pub fn new(show: &Show, names: NameVariant) -> Option<WatchView> {
+        let watch = show.watch.as_ref()?;
+        Some(WatchView {
+            show: show.get_display_name(names).to_string(),
+            status: watch.status,
+            last_episode: watch.last_episode.map(|number| number.to_string()),
+            watched: show.get_watched_count(),
+            episodes: show.get_episode_count(),
+            next: show
+                .get_next_episode()
+                .map(|(number, episode)| match &episode.title {
+                    Some(title) => format!("{number}: {title}"),
+                    None => number.to_string(),
+                }),
+            started: watch.started,
+            finished: watch.finished,
+            last_watched: watch.last_watched.map(|time| time.with_timezone(&Local)),
+        })
+    }
+
+    fn get_last_watched(&self) -> String {
+        self.last_watched
+            .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl Render for WatchView {
+    fn text(&self) -> String {
+        let mut text = format!(
+            "{} - {}, {}/{} episodes",
+            self.show, self.status, self.watched, self.episodes
+        );
+        if let Some(next) = &self.next {
+            text.push_str(&format!(", next {next}"));
+        }
+        if self.last_watched.is_some() {
+            text.push_str(&format!(", last watched {}", self.get_last_watched()));
+        }
+        text
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Show", "Status", "Watched", "Next", "Last watched"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.show.clone(),
+            self.status.to_string(),
+            format!("{}/{}", self.watched, self.episodes),
+            self.next.clone().unwrap_or_default(),
+            self.get_last_watched(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct ReviewView {
+    pub show: String,
+    /// E.g. "S1E3", None when the show itself was reviewed
+    pub episode: Option<String>,
+    pub user: String,
+    pub score: Option<u8>,
+    pub text: Option<String>,
+    pub updated: DateTime<Local>,
+}
+
+impl ReviewView {
+    pub fn new(show_name: &str, episode: Option<EpisodeNumber>, review: &Review) -> ReviewView {
+        ReviewView {
+            show: show_name.to_string(),
+            episode: episode.map(|number| number.to_string()),
+            user: review.user.clone(),
+            score: review.score,
+            text: review.text.clone(),
+            updated: review.updated.with_timezone(&Local),
+        }
+    }
+
+    fn get_target(&self) -> String {
+        match &self.episode {
+            Some(episode) => format!("{} {episode}", self.show),
+            None => self.show.clone(),
+        }
+    }
+}
+
+impl Render for ReviewView {
+    fn text(&self) -> String {
+        let mut text = format!("{} by {}", self.get_target(), self.user);
+        if let Some(score) = self.score {
+            text.push_str(&format!(" - {score}/{MAX_SCORE}"));
+        }
+        if let Some(review) = &self.text {
+            text.push_str(&format!("\n\t{review}"));
+        }
+        text
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Reviewed", "User", "Score", "Review"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.get_target(),
+            self.user.clone(),
+            self.score
+                .map(|score| score.to_string())
+                .unwrap_or_default(),
+            self.text.clone().unwrap_or_default(),
+        ]]
+    }
+}
+
+/// A show or episode in `kd rank`
+#[derive(Serialize)]
+pub struct RankView {
+    pub rank: usize,
+    pub name: String,
+    /// Average score
+    pub score: f32,
+    pub ratings: usize,
+}
+
+impl Render for RankView {
+    fn text(&self) -> String {
+        format!(
+            "{}. {} - {:.1}/{MAX_SCORE} from {} rating(s)",
+            self.rank, self.name, self.score, self.ratings
+        )
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Rank", "Name", "Score", "Ratings"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.rank.to_string(),
+            self.name.clone(),
+            format!("{:.1}", self.score),
+            self.ratings.to_string(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct ActorView {
+    pub id: String,
+    pub name: String,
+    pub birth_year: i64,
+    /// Fee per episode
+    pub fee: Option<Money>,
+}
+
+impl From<&Actor> for ActorView {
+    fn from(actor: &Actor) -> Self {
+        ActorView {
+            id: actor.id.clone(),
+            name: actor.name.clone(),
+            birth_year: actor.birth_year,
+            fee: actor.fee.clone(),
+        }
+    }
+}
+
+impl Render for ActorView {
+    fn text(&self) -> String {
+        let mut text = format!("{} - born {}", self.name, self.birth_year);
+        if let Some(fee) = &self.fee {
+            text.push_str(&format!(", fee {fee}"));
+        }
+        text
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Name", "Born", "Fee"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.name.clone(),
+            self.birth_year.to_string(),
+            self.fee.as_ref().map(Money::to_string).unwrap_or_default(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct ActorInfo {
+    #[serde(flatten)]
+    pub actor: ActorView,
+    pub filmography: Vec<CharacterView>,
+}
+
+impl ActorInfo {
+    pub fn new(actor: &Actor, show_collection: &ShowCollection, names: NameVariant) -> ActorInfo {
+        ActorInfo {
+            actor: actor.into(),
+            filmography: show_collection
+                .get_filmography(&actor.id)
+                .into_iter()
+                .map(|(show, character)| {
+                    CharacterView::new(show, character, show_collection, names)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Render for ActorInfo {
+    fn text(&self) -> String {
+        let mut message = format!("{}\nFilmography:", self.actor.text());
+        for role in self.filmography.iter() {
+            message.push_str(&format!(
+                "\n\t- {} as {} ({}) - {}",
+                role.show, role.name, role.gender, role.role
+            ));
+        }
+        message
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Show", "Character", "Role"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.filmography
+            .iter()
+            .map(|role| vec![role.show.clone(), role.name.clone(), role.role.clone()])
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    pub kind: &'static str,
+    pub show: String,
+    pub name: String,
+    #[serde(skip)]
+    text: String,
+}
+
+impl SearchHit {
+    pub fn new(result: &SearchResult, names: NameVariant) -> SearchHit {
+        let (kind, show, name, text) = match result {
+            SearchResult::Show(show) => {
+                let name = show.get_display_name(names).to_string();
+                let text = format!("Show: {name} - {}", show.release_year);
+                ("show", show, name, text)
+            }
+            SearchResult::Character(show, character) => {
+                let name = character.get_display_name(names).to_string();
+                let text = format!(
+                    "Character: {name} ({}) - {} in {}",
+                    character.gender,
+                    character.role,
+                    show.get_display_name(names)
+                );
+                ("character", show, name, text)
+            }
+            SearchResult::Relationship(show, relationship) => {
+                let name = RelationshipView::new(show, relationship, names).text();
+                let text = format!("Relationship: {name} in {}", show.get_display_name(names));
+                ("relationship", show, name, text)
+            }
+        };
+        SearchHit {
+            kind,
+            show: show.get_display_name(names).to_string(),
+            name,
+            text,
+        }
+    }
+}
+
+impl Render for SearchHit {
+    fn text(&self) -> String {
+        self.text.clone()
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Kind", "Show", "Match"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.kind.to_string(),
+            self.show.clone(),
+            self.name.clone(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct RepairReport {
+    pub orphaned: Vec<RelationshipView>,
+    pub repaired: usize,
+    #[serde(skip)]
+    pub dry_run: bool,
+}
+
+impl Render for RepairReport {
+    fn text(&self) -> String {
+        if self.orphaned.is_empty() {
+            return "No orphaned relationships found".to_string();
+        }
+        if !self.dry_run {
+            return format!(
+                "Repaired {} of {} orphaned relationship(s)",
+                self.repaired,
+                self.orphaned.len()
+            );
+        }
+        let mut message = String::new();
+        for orphan in self.orphaned.iter() {
+            message.push_str(&format!("{}: {}\n", orphan.show, orphan.text()));
+        }
+        message.push_str(&format!(
+            "Found {} orphaned relationship(s)",
+            self.orphaned.len()
+        ));
+        message
+    }
+
+    fn headers() -> Vec<&'static str> {
+        RelationshipView::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.orphaned.iter().flat_map(Render::rows).collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct MigrationView {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub changes: Vec<String>,
+    pub backup: Option<String>,
+    #[serde(skip)]
+    pub dry_run: bool,
+}
+
+impl MigrationView {
+    pub fn new(report: MigrationReport, dry_run: bool) -> MigrationView {
+        MigrationView {
+            from_version: report.from_version,
+            to_version: report.to_version,
+            changes: report.changes,
+            backup: report.backup.map(|path| path.to_string_lossy().to_string()),
+            dry_run,
+        }
+    }
+}
+
+impl Render for MigrationView {
+    fn text(&self) -> String {
+        if self.from_version == self.to_version {
+            return format!("Data file is up to date (version {})", self.to_version);
+        }
+        let mut message = format!(
+            "{} data file from version {} to {}",
+            if self.dry_run {
+                "Would migrate"
+            } else {
+                "Migrated"
+            },
+            self.from_version,
+            self.to_version
+        );
+        for change in self.changes.iter() {
+            message.push_str(&format!("\n\t- {change}"));
+        }
+        if let Some(backup) = &self.backup {
+            message.push_str(&format!("\nBackup saved to {backup:?}"));
+        }
+        message
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["From", "To", "Change"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.changes
+            .iter()
+            .map(|change| {
+                vec![
+                    self.from_version.to_string(),
+                    self.to_version.to_string(),
+                    change.clone(),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize)]
+pub struct ConvertView {
+    pub from: String,
+    pub to: String,
+    pub shows: usize,
+    pub actors: usize,
+    pub backup: String,
+}
+
+impl From<ConvertReport> for ConvertView {
+    fn from(report: ConvertReport) -> Self {
+        ConvertView {
+            from: report.from.to_string(),
+            to: report.to.to_string(),
+            shows: report.shows,
+            actors: report.actors,
+            backup: report.backup.to_string_lossy().to_string(),
+        }
+    }
+}
+
+impl Render for ConvertView {
+    fn text(&self) -> String {
+        format!(
+            "Converted {} show(s) and {} actor(s) from {} to {}\nOld data moved to {:?}",
+            self.shows, self.actors, self.from, self.to, self.backup
+        )
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["From", "To", "Shows", "Actors", "Backup"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.from.clone(),
+            self.to.clone(),
+            self.shows.to_string(),
+            self.actors.to_string(),
+            self.backup.clone(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct EntryView {
+    pub id: String,
+    pub timestamp: String,
+    pub user: String,
+    pub kind: EntryKind,
+    pub description: String,
+}
+
+impl From<&Entry> for EntryView {
+    fn from(entry: &Entry) -> Self {
+        EntryView {
+            id: entry.id.clone(),
+            timestamp: entry
+                .timestamp
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            user: entry.user.clone(),
+            kind: entry.kind,
+            description: entry.describe(),
+        }
+    }
+}
+
+impl EntryView {
+    fn get_kind_name(&self) -> &'static str {
+        match self.kind {
+            EntryKind::Change => "change",
+            EntryKind::Undo => "undo",
+            EntryKind::Redo => "redo",
+        }
+    }
+}
+
+impl Render for EntryView {
+    fn text(&self) -> String {
+        format!(
+            "{} {} [{}] {}",
+            self.timestamp,
+            self.user,
+            self.get_kind_name(),
+            self.description
+        )
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Time", "User", "Kind", "Description"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.timestamp.clone(),
+            self.user.clone(),
+            self.get_kind_name().to_string(),
+            self.description.clone(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct ConfigView {
+    pub config_dir: String,
+    pub data_dir: String,
+    pub library: String,
+    pub libraries: Vec<String>,
+}
+
+impl Render for ConfigView {
+    fn text(&self) -> String {
+        format!(
+            "Config directory: {:?}\nData directory: {:?}\nLibrary: {}\nLibraries: {}",
+            self.config_dir,
+            self.data_dir,
+            self.library,
+            self.libraries.join(", ")
+        )
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Config directory", "Data directory", "Library"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.config_dir.clone(),
+            self.data_dir.clone(),
+            self.library.clone(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct SettingView {
+    pub key: String,
+    pub value: Option<String>,
+}
+
+impl Render for SettingView {
+    fn text(&self) -> String {
+        format!("{} = {}", self.key, self.value.as_deref().unwrap_or(""))
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Key", "Value"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            self.key.clone(),
+            self.value.clone().unwrap_or_default(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct Conversion {
+    pub amount: f64,
+    pub from: String,
+    pub converted: f64,
+    pub to: String,
+    /// Units of `to` per unit of `from`
+    pub rate: f64,
+    pub as_of: DateTime<Utc>,
+    /// Day the rate was asked for, None for the latest rate
+    pub date: Option<NaiveDate>,
+    /// The converted amount adjusted for inflation since `date`
+    pub adjusted: Option<f64>,
+    /// Why the amount wasn't adjusted for inflation
+    pub note: Option<String>,
+}
+
+impl Conversion {
+    /// Both amounts and the adjusted one, won in Korean units and other currencies with
+    /// the locale's separators
+    fn format_amounts(&self) -> (String, String, Option<String>) {
+        let locale = get_locale();
+        let format = |amount: f64, currency: &str| {
+            format!("{} {currency}", format_currency(amount, currency, &locale))
+        };
+        (
+            format(self.amount, &self.from),
+            format(self.converted, &self.to),
+            self.adjusted.map(|adjusted| format(adjusted, &self.to)),
+        )
+    }
+}
+
+impl Render for Conversion {
+    fn text(&self) -> String {
+        let (from, to, adjusted) = self.format_amounts();
+        let mut text = format!("{from} = {to}");
+        if let Some(date) = self.date {
+            text.push_str(&format!(" on {date}"));
+        }
+        if let Some(adjusted) = adjusted {
+            text.push_str(&format!(", {adjusted} today adjusted for inflation"));
+        }
+        if let Some(note) = &self.note {
+            text.push_str(&format!("\n{note}"));
+        }
+        text
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["From", "To", "Rate", "As of", "Adjusted", "Note"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let (from, to, adjusted) = self.format_amounts();
+        vec![vec![
+            from,
+            to,
+            self.rate.to_string(),
+            self.as_of.format("%Y-%m-%d %H:%M").to_string(),
+            adjusted.unwrap_or_default(),
+            self.note.clone().unwrap_or_default(),
+        ]]
+    }
+}
+
+#[derive(Serialize)]
+pub struct Romanization {
+    pub hangul: String,
+    pub romanized: String,
+}
+
+impl Render for Romanization {
+    fn text(&self) -> String {
+        self.romanized.clone()
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Hangul", "Romanized"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.hangul.clone(), self.romanized.clone()]]
+    }
+}
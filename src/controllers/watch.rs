@@ -0,0 +1,87 @@
+use inquire::Select;
+use kd::{
+    models::{show_collection::ShowCollection, watch::WatchStatus},
+    Error,
+};
+
+use super::{
+    find_show, resolve_show_name,
+    views::{Done, WatchView},
+    Context, ControllerResult,
+};
+
+const STATUSES: [WatchStatus; 5] = [
+    WatchStatus::PlanToWatch,
+    WatchStatus::Watching,
+    WatchStatus::Completed,
+    WatchStatus::Dropped,
+    WatchStatus::OnHold,
+];
+
+pub fn next_controller(ctx: &Context, show_name: Option<String>) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "<SHOW_NAME>")?;
+    find_show(ctx, &show_collection, &show_name)?;
+
+    show_collection.watch_next(&show_name)?;
+    show_collection
+        .save()
+        .map_err(|e| e.context("Unable to save show collection"))?;
+    ctx.print(&Done::new(
+        "Watched",
+        watch_view(ctx, &show_collection, &show_name)?,
+    ))
+}
+
+pub fn set_controller(
+    ctx: &Context,
+    show_name: Option<String>,
+    status: Option<WatchStatus>,
+) -> ControllerResult {
+    let mut show_collection =
+        ShowCollection::load().map_err(|e| e.context("Unable to load shows"))?;
+    let show_name = resolve_show_name(ctx, &show_collection, show_name, "<SHOW_NAME>")?;
+    let show = find_show(ctx, &show_collection, &show_name)?;
+    let current = show.watch.as_ref().map(|w| w.status);
+    let status = ctx.resolve(status, "--status", || {
+        let cursor = STATUSES
+            .iter()
+            .position(|status| Some(*status) == current)
+            .unwrap_or(0);
+        Select::new("Status:", STATUSES.to_vec())
+            .with_starting_cursor(cursor)
+            .with_vim_mode(true)
+            .prompt()
+    })?;
+
+    show_collection.set_watch_status(&show_name, status)?;
+    show_collection
+        .save()
+        .map_err(|e| e.context("Unable to save show collection"))?;
+    ctx.print(&Done::new(
+        "Updated status",
+        watch_view(ctx, &show_collection, &show_name)?,
+    ))
+}
+
+pub fn status_controller(ctx: &Context, status: WatchStatus) -> ControllerResult {
+    let show_collection = ShowCollection::read().map_err(|e| e.context("Unable to load shows"))?;
+    let shows: Vec<WatchView> = show_collection
+        .list_watching(status)
+        .into_iter()
+        .filter_map(|show| WatchView::new(show, ctx.settings.names))
+        .collect();
+    ctx.print_list(shows, &format!("No shows are {status}"))
+}
+
+fn watch_view(
+    ctx: &Context,
+    show_collection: &ShowCollection,
+    show_name: &str,
+) -> Result<WatchView, Error> {
+    show_collection
+        .get_show_by_name(show_name)
+        .and_then(|show| WatchView::new(show, ctx.settings.names))
+        .ok_or_else(|| Error::not_found("show", show_name))
+}
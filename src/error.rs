@@ -0,0 +1,229 @@
+//! The error type returned throughout the library
+use std::fmt::Display;
+
+/// Everything that can go wrong in kd, grouped by what the caller can do about it
+#[derive(Debug)]
+pub enum Error {
+    /// A show, character, actor or other record doesn't exist. `suggestions` are similar
+    /// names that do
+    NotFound {
+        kind: &'static str,
+        name: String,
+        suggestions: Vec<String>,
+    },
+    /// A record with the same identity already exists
+    Duplicate { kind: &'static str, name: String },
+    /// Input that doesn't make sense, e.g. an unknown setting or an empty name
+    Validation(String),
+    /// Stored data or a response couldn't be read or written as JSON, YAML or TOML
+    Parse(String),
+    /// A file or database couldn't be read or written
+    Io(String),
+    /// Another kd instance holds the lock, or the data changed underneath an undo
+    Conflict(String),
+    /// A remote service couldn't be reached or answered with an error
+    Network(String),
+    /// The user cancelled a prompt with Esc or Ctrl-C
+    Cancelled,
+    /// Another error along with what was being done when it happened
+    Context { context: String, source: Box<Error> },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn not_found(kind: &'static str, name: impl Into<String>) -> Error {
+        Error::NotFound {
+            kind,
+            name: name.into(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn duplicate(kind: &'static str, name: impl Into<String>) -> Error {
+        Error::Duplicate {
+            kind,
+            name: name.into(),
+        }
+    }
+
+    /// Adds "did you mean" suggestions to a [`Error::NotFound`], other errors are returned as is
+    pub fn with_suggestions(self, suggestions: Vec<String>) -> Error {
+        match self {
+            Error::NotFound { kind, name, .. } => Error::NotFound {
+                kind,
+                name,
+                suggestions,
+            },
+            error => error,
+        }
+    }
+
+    /// Wraps the error with a description of what was being done, e.g. "Unable to load shows"
+    pub fn context(self, context: impl Into<String>) -> Error {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The error without any [`Error::Context`] around it
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// Process exit code for the error. These are stable so scripts can rely on them:
+    ///
+    /// | Code | Error      |
+    /// |------|------------|
+    /// | 3    | NotFound   |
+    /// | 4    | Duplicate  |
+    /// | 5    | Validation |
+    /// | 6    | Parse      |
+    /// | 7    | Io         |
+    /// | 8    | Conflict   |
+    /// | 9    | Network    |
+    /// | 130  | Cancelled  |
+    pub fn exit_code(&self) -> i32 {
+        match self.root() {
+            Error::NotFound { .. } => 3,
+            Error::Duplicate { .. } => 4,
+            Error::Validation(_) => 5,
+            Error::Parse(_) => 6,
+            Error::Io(_) => 7,
+            Error::Conflict(_) => 8,
+            Error::Network(_) => 9,
+            Error::Cancelled => 130,
+            Error::Context { .. } => unreachable!("root is never a context"),
+        }
+    }
+
+    /// Name of the variant of the underlying error, e.g. not_found, for scripts to match on
+    pub fn kind(&self) -> &'static str {
+        match self.root() {
+            Error::NotFound { .. } => "not_found",
+            Error::Duplicate { .. } => "duplicate",
+            Error::Validation(_) => "validation",
+            Error::Parse(_) => "parse",
+            Error::Io(_) => "io",
+            Error::Conflict(_) => "conflict",
+            Error::Network(_) => "network",
+            Error::Cancelled => "cancelled",
+            Error::Context { .. } => unreachable!("root is never a context"),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::NotFound {
+                kind,
+                name,
+                suggestions,
+            } => {
+                write!(f, "Unable to find {kind} \"{name}\"")?;
+                if !suggestions.is_empty() {
+                    let suggestions: Vec<String> =
+                        suggestions.iter().map(|s| format!("\"{s}\"")).collect();
+                    write!(f, ", did you mean {}?", suggestions.join(" or "))?;
+                }
+                Ok(())
+            }
+            Error::Duplicate { kind, name } => {
+                let article = match kind.starts_with(['a', 'e', 'i', 'o', 'u']) {
+                    true => "An",
+                    false => "A",
+                };
+                write!(f, "{article} {kind} named \"{name}\" already exists")
+            }
+            Error::Validation(message)
+            | Error::Parse(message)
+            | Error::Io(message)
+            | Error::Conflict(message)
+            | Error::Network(message) => write!(f, "{message}"),
+            Error::Cancelled => write!(f, "Cancelled"),
+            Error::Context { context, source } => write!(f, "{context}: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(error: serde_yaml::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(error: toml::ser::Error) -> Self {
+        Error::Parse(error.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Error::Io(format!("Database error: {error}"))
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Network(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+
+    #[test]
+    fn should_keep_exit_code_through_context() {
+        let error = Error::not_found("show", "Goblin").context("Unable to load shows");
+        assert_eq!(error.exit_code(), 3);
+        assert_eq!(
+            error.to_string(),
+            "Unable to load shows: Unable to find show \"Goblin\""
+        );
+        assert!(matches!(error.root(), Error::NotFound { kind: "show", .. }));
+    }
+
+    #[test]
+    fn should_list_suggestions() {
+        let error = Error::not_found("show", "Gobln")
+            .with_suggestions(vec!["Goblin".to_string(), "Gobi".to_string()]);
+        assert_eq!(
+            error.to_string(),
+            "Unable to find show \"Gobln\", did you mean \"Goblin\" or \"Gobi\"?"
+        );
+    }
+}
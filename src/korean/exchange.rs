@@ -0,0 +1,568 @@
+//! Exchange rates between currencies, fetched from a provider and cached on disk
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::Error, storage};
+
+/// Where Alpha Vantage is reached unless the `rates_url` setting says otherwise
+pub const ALPHA_VANTAGE_URL: &str = "https://www.alphavantage.co";
+/// Environment variable holding the Alpha Vantage API key, `.env` in the config directory
+/// may set it
+pub const API_KEY_VAR: &str = "ALPHA_VANTAGE_API_KEY";
+/// File in the data directory rates are cached in
+pub const CACHE_FILE_NAME: &str = "rates.json";
+
+/// Checks `code` is a three letter currency code such as KRW, returning it in uppercase
+pub fn parse_currency(code: &str) -> Result<String, Error> {
+    let code = code.trim();
+    match code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+        true => Ok(code.to_uppercase()),
+        false => Err(Error::Validation(format!(
+            "'{code}' isn't a currency code, expected one like KRW, USD or EUR"
+        ))),
+    }
+}
+
+/// How many units of one currency a unit of another is worth
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct Rate {
+    pub rate: f64,
+    /// When the rate was fetched, or the day it closed on for historical rates
+    pub as_of: DateTime<Utc>,
+}
+
+/// A source of exchange rates, currencies are ISO 4217 codes such as KRW
+pub trait ExchangeRateProvider {
+    /// The rate on `date`, the latest one when None. Days without trading have the
+    /// previous day's closing rate
+    fn get_rate(&self, from: &str, to: &str, date: Option<NaiveDate>) -> Result<Rate, Error>;
+}
+
+/// A source of consumer price indexes, used to adjust amounts for inflation
+pub trait PriceIndexProvider {
+    /// Index for the country of `currency` in `year`, or the latest year before it when
+    /// that year isn't published yet
+    fn get_price_index(&self, currency: &str, year: i32) -> Result<f64, Error>;
+}
+
+impl<P: ExchangeRateProvider + ?Sized> ExchangeRateProvider for &P {
+    fn get_rate(&self, from: &str, to: &str, date: Option<NaiveDate>) -> Result<Rate, Error> {
+        (**self).get_rate(from, to, date)
+    }
+}
+
+impl<P: PriceIndexProvider + ?Sized> PriceIndexProvider for &P {
+    fn get_price_index(&self, currency: &str, year: i32) -> Result<f64, Error> {
+        (**self).get_price_index(currency, year)
+    }
+}
+
+/// Realtime and daily rates and the US consumer price index from the Alpha Vantage API
+pub struct AlphaVantage {
+    base_url: String,
+    /// Only needed once a rate is fetched, so cached rates work without one
+    api_key: Option<String>,
+}
+
+impl AlphaVantage {
+    pub fn new(base_url: &str, api_key: Option<String>) -> AlphaVantage {
+        AlphaVantage {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+
+    /// Uses the API key from the environment
+    pub fn from_env(base_url: &str) -> AlphaVantage {
+        let api_key = std::env::var(API_KEY_VAR)
+            .ok()
+            .filter(|key| !key.is_empty());
+        AlphaVantage::new(base_url, api_key)
+    }
+
+    /// Calls the API, returning the part of the response under `key`. Alpha Vantage
+    /// answers errors and rate limiting with a message instead of `key`
+    fn query(&self, query: &str, key: &str) -> Result<Value, Error> {
+        let api_key = self
+            .api_key
+            .as_ref()
+            .ok_or_else(|| Error::Validation(format!("{API_KEY_VAR} isn't set")))?;
+        let url = format!("{}/query?{query}&apikey={api_key}", self.base_url);
+        let body = reqwest::blocking::get(url)?.error_for_status()?.text()?;
+
+        let mut json: Value = serde_json::from_str(&body)?;
+        match json.get_mut(key).map(Value::take) {
+            Some(value) => Ok(value),
+            None => Err(Error::Network(format!("Unexpected response: {body}"))),
+        }
+    }
+
+    fn get_latest_rate(&self, from: &str, to: &str) -> Result<Rate, Error> {
+        let json = self.query(
+            &format!("function=CURRENCY_EXCHANGE_RATE&from_currency={from}&to_currency={to}"),
+            "Realtime Currency Exchange Rate",
+        )?;
+        let rate = match json.get("5. Exchange Rate") {
+            Some(value) => match value.as_str() {
+                Some(value) => value,
+                None => return Err(Error::Parse("Exchange rate isn't a string".to_string())),
+            },
+            None => return Err(Error::Parse("Response has no exchange rate".to_string())),
+        };
+        Ok(Rate {
+            rate: parse_number(rate)?,
+            as_of: Utc::now(),
+        })
+    }
+
+    fn get_daily_rate(&self, from: &str, to: &str, date: NaiveDate) -> Result<Rate, Error> {
+        let json = self.query(
+            &format!("function=FX_DAILY&from_symbol={from}&to_symbol={to}&outputsize=full"),
+            "Time Series FX (Daily)",
+        )?;
+        let days = json
+            .as_object()
+            .ok_or_else(|| Error::Parse("Daily rates aren't an object".to_string()))?;
+        // Days are YYYY-MM-DD, so the latest one on or before the date sorts last
+        let wanted = date.to_string();
+        let (day, rates) = days
+            .iter()
+            .filter(|(day, _)| **day <= wanted)
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .ok_or_else(|| {
+                Error::Validation(format!("No {from} to {to} rate on or before {date}"))
+            })?;
+        let rate = rates
+            .get("4. close")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Parse(format!("No closing rate on {day}")))?;
+        Ok(Rate {
+            rate: parse_number(rate)?,
+            as_of: parse_day(day)?,
+        })
+    }
+}
+
+impl Default for AlphaVantage {
+    fn default() -> Self {
+        AlphaVantage::from_env(ALPHA_VANTAGE_URL)
+    }
+}
+
+impl ExchangeRateProvider for AlphaVantage {
+    fn get_rate(&self, from: &str, to: &str, date: Option<NaiveDate>) -> Result<Rate, Error> {
+        match date {
+            Some(date) => self.get_daily_rate(from, to, date),
+            None => self.get_latest_rate(from, to),
+        }
+    }
+}
+
+impl PriceIndexProvider for AlphaVantage {
+    fn get_price_index(&self, currency: &str, year: i32) -> Result<f64, Error> {
+        if !currency.eq_ignore_ascii_case("USD") {
+            return Err(Error::Validation(format!(
+                "No price index for {currency}, Alpha Vantage only has the US one"
+            )));
+        }
+        let json = self.query("function=CPI&interval=annual", "data")?;
+        let years = json
+            .as_array()
+            .ok_or_else(|| Error::Parse("Price indexes aren't a list".to_string()))?;
+        let mut latest: Option<(i32, &str)> = None;
+        for entry in years {
+            let (Some(date), Some(value)) = (
+                entry.get("date").and_then(Value::as_str),
+                entry.get("value").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            let entry_year = parse_day(date)?.year();
+            if entry_year <= year && latest.is_none_or(|(latest, _)| entry_year > latest) {
+                latest = Some((entry_year, value));
+            }
+        }
+        match latest {
+            Some((_, value)) => parse_number(value),
+            None => Err(Error::Validation(format!("No price index for {year}"))),
+        }
+    }
+}
+
+fn parse_number(value: &str) -> Result<f64, Error> {
+    value
+        .parse::<f64>()
+        .map_err(|_| Error::Parse(format!("'{value}' isn't a number")))
+}
+
+/// Midnight UTC on a YYYY-MM-DD day
+fn parse_day(day: &str) -> Result<DateTime<Utc>, Error> {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .ok()
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|day| day.and_utc())
+        .ok_or_else(|| Error::Parse(format!("'{day}' isn't a date")))
+}
+
+/// Rates and price indexes set by hand, e.g. with `kd convert --rate`. The reverse of each
+/// rate is known too and rates are the same on every date
+#[derive(Default)]
+pub struct FixedRates {
+    rates: HashMap<String, f64>,
+    indexes: HashMap<String, Vec<(i32, f64)>>,
+}
+
+impl FixedRates {
+    pub fn new() -> FixedRates {
+        FixedRates::default()
+    }
+
+    pub fn with_rate(mut self, from: &str, to: &str, rate: f64) -> FixedRates {
+        self.rates.insert(pair_key(from, to, None), rate);
+        self
+    }
+
+    pub fn with_price_index(mut self, currency: &str, year: i32, index: f64) -> FixedRates {
+        self.indexes
+            .entry(currency.to_uppercase())
+            .or_default()
+            .push((year, index));
+        self
+    }
+}
+
+impl ExchangeRateProvider for FixedRates {
+    fn get_rate(&self, from: &str, to: &str, _date: Option<NaiveDate>) -> Result<Rate, Error> {
+        let rate = if from.eq_ignore_ascii_case(to) {
+            Some(1.0)
+        } else {
+            self.rates
+                .get(&pair_key(from, to, None))
+                .copied()
+                .or_else(|| {
+                    self.rates
+                        .get(&pair_key(to, from, None))
+                        .map(|rate| 1.0 / rate)
+                })
+        };
+        match rate {
+            Some(rate) => Ok(Rate {
+                rate,
+                as_of: Utc::now(),
+            }),
+            None => Err(Error::Validation(format!(
+                "No rate set from {from} to {to}"
+            ))),
+        }
+    }
+}
+
+impl PriceIndexProvider for FixedRates {
+    fn get_price_index(&self, currency: &str, year: i32) -> Result<f64, Error> {
+        self.indexes
+            .get(&currency.to_uppercase())
+            .and_then(|indexes| {
+                indexes
+                    .iter()
+                    .filter(|(index_year, _)| *index_year <= year)
+                    .max_by_key(|(index_year, _)| *index_year)
+            })
+            .map(|(_, index)| *index)
+            .ok_or_else(|| {
+                Error::Validation(format!("No price index set for {currency} in {year}"))
+            })
+    }
+}
+
+/// Has no rates of its own, so a [`CachedProvider`] around it only answers with what's
+/// already cached and never waits on the network
+pub struct CacheOnly;
+
+impl ExchangeRateProvider for CacheOnly {
+    fn get_rate(&self, from: &str, to: &str, date: Option<NaiveDate>) -> Result<Rate, Error> {
+        let on = date.map(|date| format!(" for {date}")).unwrap_or_default();
+        Err(Error::Validation(format!(
+            "no cached {}/{} rate{on}",
+            from.to_uppercase(),
+            to.to_uppercase()
+        )))
+    }
+}
+
+/// Keeps the rates and price indexes another provider fetches in a file, asking it again
+/// once they're older than the time to live. Rates for past days and indexes for years
+/// long published don't change, so they're kept for good. When the provider fails an
+/// expired value is used instead, so conversions keep working offline
+pub struct CachedProvider<P> {
+    provider: P,
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl<P> CachedProvider<P> {
+    /// Caches in [`CACHE_FILE_NAME`] in `data_dir`
+    pub fn new(provider: P, data_dir: &Path, ttl: Duration) -> CachedProvider<P> {
+        CachedProvider {
+            provider,
+            path: data_dir.join(CACHE_FILE_NAME),
+            ttl,
+        }
+    }
+
+    fn read(&self) -> HashMap<String, Rate> {
+        // A missing or unreadable cache is the same as an empty one
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn write(&self, rates: &HashMap<String, Rate>) -> Result<(), Error> {
+        let content = serde_json::to_string_pretty(rates)?;
+        storage::write_atomic(&self.path, content.as_bytes())
+    }
+
+    /// The value cached under `key`, or the reverse of the one under `reverse_key`, while
+    /// it's fresh. Otherwise fetches and caches it, falling back to the cached value
+    fn get_or_fetch(
+        &self,
+        key: String,
+        reverse_key: Option<String>,
+        permanent: bool,
+        fetch: impl FnOnce() -> Result<Rate, Error>,
+    ) -> Result<Rate, Error> {
+        let mut rates = self.read();
+        let cached = rates.get(&key).copied().or_else(|| {
+            let rate = rates.get(reverse_key.as_ref()?)?;
+            Some(Rate {
+                rate: 1.0 / rate.rate,
+                as_of: rate.as_of,
+            })
+        });
+        if let Some(rate) = cached.filter(|rate| permanent || Utc::now() - rate.as_of < self.ttl) {
+            return Ok(rate);
+        }
+        match fetch() {
+            Ok(rate) => {
+                rates.insert(key, rate);
+                // The rate is still good when the cache can't be written
+                let _ = self.write(&rates);
+                Ok(rate)
+            }
+            Err(e) => cached.ok_or(e),
+        }
+    }
+}
+
+impl<P: ExchangeRateProvider> ExchangeRateProvider for CachedProvider<P> {
+    fn get_rate(&self, from: &str, to: &str, date: Option<NaiveDate>) -> Result<Rate, Error> {
+        let permanent = date.is_some_and(|date| date < Utc::now().date_naive());
+        self.get_or_fetch(
+            pair_key(from, to, date),
+            Some(pair_key(to, from, date)),
+            permanent,
+            || self.provider.get_rate(from, to, date),
+        )
+    }
+}
+
+impl<P: PriceIndexProvider> PriceIndexProvider for CachedProvider<P> {
+    fn get_price_index(&self, currency: &str, year: i32) -> Result<f64, Error> {
+        // A year's index is published during the next year
+        let permanent = year < Utc::now().year() - 1;
+        let key = format!("CPI:{}@{year}", currency.to_uppercase());
+        let index = self.get_or_fetch(key, None, permanent, || {
+            Ok(Rate {
+                rate: self.provider.get_price_index(currency, year)?,
+                as_of: Utc::now(),
+            })
+        })?;
+        Ok(index.rate)
+    }
+}
+
+/// Key rates are cached under, e.g. KRW/USD or KRW/USD@2016-12-02
+fn pair_key(from: &str, to: &str, date: Option<NaiveDate>) -> String {
+    let pair = format!("{}/{}", from.to_uppercase(), to.to_uppercase());
+    match date {
+        Some(date) => format!("{pair}@{date}"),
+        None => pair,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::Cell,
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use chrono::{Duration, NaiveDate, Utc};
+
+    use super::{
+        AlphaVantage, CachedProvider, ExchangeRateProvider, FixedRates, PriceIndexProvider, Rate,
+    };
+    use crate::{error::Error, testing::TempDir};
+
+    /// Serves `body` to a single request, returning the base URL to reach it at
+    fn serve_once(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://{address}")
+    }
+
+    fn stub(body: &'static str) -> AlphaVantage {
+        AlphaVantage::new(&serve_once(body), Some("demo".to_string()))
+    }
+
+    #[test]
+    fn should_get_latest_rate_from_stub_server() {
+        let provider = stub(
+            r#"{"Realtime Currency Exchange Rate": {"1. From_Currency Code": "KRW", "5. Exchange Rate": "0.00072"}}"#,
+        );
+        assert_eq!(provider.get_rate("KRW", "USD", None).unwrap().rate, 0.00072);
+    }
+
+    #[test]
+    fn should_get_rate_of_latest_day_on_or_before_date() {
+        // Saturday has the rate Friday closed at
+        let provider = stub(
+            r#"{"Time Series FX (Daily)": {"2016-12-05": {"4. close": "0.00086"}, "2016-12-02": {"4. close": "0.00085"}}}"#,
+        );
+        let date = NaiveDate::from_ymd_opt(2016, 12, 3);
+        let rate = provider.get_rate("KRW", "USD", date).unwrap();
+        assert_eq!(rate.rate, 0.00085);
+        assert_eq!(rate.as_of.date_naive().to_string(), "2016-12-02");
+    }
+
+    #[test]
+    fn should_get_price_index_of_latest_year_on_or_before() {
+        let provider = stub(
+            r#"{"data": [{"date": "2023-01-01", "value": "304.702"}, {"date": "2016-01-01", "value": "240.007"}]}"#,
+        );
+        assert_eq!(provider.get_price_index("USD", 2024).unwrap(), 304.702);
+    }
+
+    #[test]
+    fn should_report_rate_limits_and_missing_api_key() {
+        let provider = stub(r#"{"Note": "Thank you for using Alpha Vantage!"}"#);
+        assert!(matches!(
+            provider.get_rate("KRW", "USD", None),
+            Err(Error::Network(_))
+        ));
+
+        let provider = AlphaVantage::new("http://127.0.0.1:1", None);
+        assert!(matches!(
+            provider.get_rate("KRW", "USD", None),
+            Err(Error::Validation(_))
+        ));
+    }
+
+    /// Counts requests and fails once it's told to go offline
+    struct Counting {
+        requests: Cell<usize>,
+        offline: Cell<bool>,
+    }
+
+    impl Counting {
+        fn new() -> Counting {
+            Counting {
+                requests: Cell::new(0),
+                offline: Cell::new(false),
+            }
+        }
+    }
+
+    impl ExchangeRateProvider for Counting {
+        fn get_rate(
+            &self,
+            _from: &str,
+            _to: &str,
+            _date: Option<NaiveDate>,
+        ) -> Result<Rate, Error> {
+            self.requests.set(self.requests.get() + 1);
+            match self.offline.get() {
+                true => Err(Error::Network("offline".to_string())),
+                false => Ok(Rate {
+                    rate: 0.00072,
+                    as_of: Utc::now() - Duration::hours(2),
+                }),
+            }
+        }
+    }
+
+    #[test]
+    fn should_cache_rates_and_their_reverse_until_they_expire() {
+        let dir = TempDir::new();
+        let counting = Counting::new();
+        let cached = CachedProvider::new(&counting, dir.path(), Duration::hours(12));
+        cached.get_rate("KRW", "USD", None).unwrap();
+        cached.get_rate("krw", "usd", None).unwrap();
+        let reverse = cached.get_rate("USD", "KRW", None).unwrap();
+        assert_eq!(reverse.rate, 1.0 / 0.00072);
+        assert_eq!(counting.requests.get(), 1);
+
+        let expired = CachedProvider::new(&counting, dir.path(), Duration::hours(1));
+        expired.get_rate("KRW", "USD", None).unwrap();
+        assert_eq!(counting.requests.get(), 2);
+    }
+
+    #[test]
+    fn should_fall_back_to_expired_rates_while_offline() {
+        let dir = TempDir::new();
+        let counting = Counting::new();
+        CachedProvider::new(&counting, dir.path(), Duration::hours(12))
+            .get_rate("KRW", "USD", None)
+            .unwrap();
+
+        let expired = CachedProvider::new(&counting, dir.path(), Duration::hours(1));
+        counting.offline.set(true);
+        assert_eq!(expired.get_rate("KRW", "USD", None).unwrap().rate, 0.00072);
+        assert_eq!(counting.requests.get(), 2);
+        // Nothing cached to fall back to
+        assert!(expired.get_rate("KRW", "JPY", None).is_err());
+    }
+
+    #[test]
+    fn should_keep_rates_of_past_days() {
+        let dir = TempDir::new();
+        let counting = Counting::new();
+        let cached = CachedProvider::new(&counting, dir.path(), Duration::zero());
+        let date = NaiveDate::from_ymd_opt(2016, 12, 2);
+        cached.get_rate("KRW", "USD", date).unwrap();
+        cached.get_rate("KRW", "USD", date).unwrap();
+        assert_eq!(counting.requests.get(), 1);
+    }
+
+    #[test]
+    fn should_convert_with_fixed_rates() {
+        let fixed = FixedRates::new()
+            .with_rate("USD", "KRW", 1250.0)
+            .with_price_index("USD", 2016, 240.0);
+        assert_eq!(
+            fixed.get_rate("KRW", "USD", None).unwrap().rate,
+            1.0 / 1250.0
+        );
+        assert_eq!(fixed.get_rate("KRW", "KRW", None).unwrap().rate, 1.0);
+        assert_eq!(fixed.get_price_index("usd", 2020).unwrap(), 240.0);
+        assert!(fixed.get_price_index("USD", 2015).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+//! Splitting precomposed Hangul syllables into their jamo
+
+/// First precomposed Hangul syllable, 가
+const SYLLABLE_BASE: u32 = 0xAC00;
+/// Syllables per initial consonant, 21 vowels with 28 finals each
+const SYLLABLES_PER_INITIAL: u32 = 588;
+const FINALS: u32 = 28;
+const SYLLABLE_COUNT: u32 = 11172;
+
+/// Initial consonants as the compatibility jamo typed on a keyboard
+const INITIALS: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ',
+    'ㅌ', 'ㅍ', 'ㅎ',
+];
+/// Keys typed for each vowel on a dubeolsik keyboard, e.g. ㅘ is ㅗ then ㅏ
+const VOWEL_KEYS: [&str; 21] = [
+    "ㅏ", "ㅐ", "ㅑ", "ㅒ", "ㅓ", "ㅔ", "ㅕ", "ㅖ", "ㅗ", "ㅗㅏ", "ㅗㅐ", "ㅗㅣ", "ㅛ", "ㅜ",
+    "ㅜㅓ", "ㅜㅔ", "ㅜㅣ", "ㅠ", "ㅡ", "ㅡㅣ", "ㅣ",
+];
+/// Keys typed for each final, e.g. ㄺ is ㄹ then ㄱ
+const FINAL_KEYS: [&str; 28] = [
+    "", "ㄱ", "ㄲ", "ㄱㅅ", "ㄴ", "ㄴㅈ", "ㄴㅎ", "ㄷ", "ㄹ", "ㄹㄱ", "ㄹㅁ", "ㄹㅂ", "ㄹㅅ",
+    "ㄹㅌ", "ㄹㅍ", "ㄹㅎ", "ㅁ", "ㅂ", "ㅂㅅ", "ㅅ", "ㅆ", "ㅇ", "ㅈ", "ㅊ", "ㅋ", "ㅌ", "ㅍ",
+    "ㅎ",
+];
+
+/// Indexes of a syllable's initial consonant, vowel and final, 0 being no final
+#[derive(Clone, Copy)]
+pub struct Syllable {
+    pub initial: usize,
+    pub vowel: usize,
+    pub last: usize,
+}
+
+impl Syllable {
+    /// None for anything but a precomposed Hangul syllable
+    pub fn from_char(c: char) -> Option<Syllable> {
+        let index = (c as u32).checked_sub(SYLLABLE_BASE)?;
+        if index >= SYLLABLE_COUNT {
+            return None;
+        }
+        Some(Syllable {
+            initial: (index / SYLLABLES_PER_INITIAL) as usize,
+            vowel: (index % SYLLABLES_PER_INITIAL / FINALS) as usize,
+            last: (index % FINALS) as usize,
+        })
+    }
+
+    /// The initial consonant, e.g. ㄷ for 도
+    pub fn get_initial(&self) -> char {
+        INITIALS[self.initial]
+    }
+
+    /// The jamo in the order they're typed, e.g. ㄷ ㅗ ㅏ ㄹ ㄱ for 돩
+    pub fn get_keys(&self) -> Vec<char> {
+        std::iter::once(self.get_initial())
+            .chain(VOWEL_KEYS[self.vowel].chars())
+            .chain(FINAL_KEYS[self.last].chars())
+            .collect()
+    }
+}
+
+/// Whether `c` is a consonant that starts syllables, e.g. ㄷ but not ㄳ or 도
+pub fn is_initial(c: char) -> bool {
+    INITIALS.contains(&c)
+}
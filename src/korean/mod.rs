@@ -1 +1,6 @@
-pub mod utils;
\ No newline at end of file
+pub mod exchange;
+pub mod jamo;
+pub mod number;
+pub mod romanize;
+pub mod search;
+pub mod utils;
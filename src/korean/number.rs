@@ -0,0 +1,248 @@
+//! Numbers written with Korean units and number words, e.g. 1.5억, 3천만원 or 스물다섯
+use num_format::{Locale, ToFormattedString};
+
+use crate::error::Error;
+
+/// Units that multiply everything before them back to the previous one, e.g. the 억 in
+/// "3천5백억"
+const LARGE_UNITS: [(char, f64); 4] = [('만', 1e4), ('억', 1e8), ('조', 1e12), ('경', 1e16)];
+/// Units that only multiply the number right before them, e.g. the 천 in "3천"
+const SMALL_UNITS: [(char, f64); 3] = [('십', 10.0), ('백', 100.0), ('천', 1000.0)];
+
+/// Sino-Korean digits, e.g. the 삼 in 삼천
+const SINO_DIGITS: [char; 10] = ['영', '일', '이', '삼', '사', '오', '육', '칠', '팔', '구'];
+/// Native Korean tens, including the forms used before counters
+const NATIVE_TENS: [(&str, f64); 10] = [
+    ("열", 10.0),
+    ("스물", 20.0),
+    ("스무", 20.0),
+    ("서른", 30.0),
+    ("마흔", 40.0),
+    ("쉰", 50.0),
+    ("예순", 60.0),
+    ("일흔", 70.0),
+    ("여든", 80.0),
+    ("아흔", 90.0),
+];
+/// Native Korean ones, including the forms used before counters
+const NATIVE_ONES: [(&str, f64); 15] = [
+    ("하나", 1.0),
+    ("한", 1.0),
+    ("둘", 2.0),
+    ("두", 2.0),
+    ("셋", 3.0),
+    ("세", 3.0),
+    ("넷", 4.0),
+    ("네", 4.0),
+    ("다섯", 5.0),
+    ("여섯", 6.0),
+    ("일곱", 7.0),
+    ("여덟", 8.0),
+    ("아홉", 9.0),
+    ("석", 3.0),
+    ("넉", 4.0),
+];
+
+/// Parses an amount in digits or Sino-Korean number words with Korean units, or in native
+/// Korean number words up to 99, optionally with commas, spaces and a won sign, e.g.
+/// "10,000,000", "1.5억", "3천만원", "1억 2천만", "삼천오백" or "스물다섯"
+/// # Examples
+/// ```
+/// use kd::korean::number::parse_amount;
+/// assert_eq!(parse_amount("1.5억").unwrap(), 150_000_000.0);
+/// assert_eq!(parse_amount("3천만원").unwrap(), 30_000_000.0);
+/// assert_eq!(parse_amount("이천오백").unwrap(), 2_500.0);
+/// assert_eq!(parse_amount("스물다섯").unwrap(), 25.0);
+/// ```
+pub fn parse_amount(text: &str) -> Result<f64, Error> {
+    let invalid = || Error::Validation(format!("'{text}' isn't an amount"));
+    let trimmed = text.trim().trim_start_matches('₩').trim_end_matches('원');
+    if let Some(native) = parse_native(trimmed) {
+        return Ok(native);
+    }
+    let mut total = 0.0;
+    // Amount since the last large unit
+    let mut section = 0.0;
+    let mut number: Option<f64> = None;
+    let mut digits = String::new();
+    let mut has_unit = false;
+    // Units have to get smaller, large ones across the amount and small ones within a section
+    let mut last_large: Option<f64> = None;
+    let mut last_small: Option<f64> = None;
+
+    let mut chars = trimmed.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' || c == ',' {
+            if number.is_some() {
+                return Err(invalid());
+            }
+            digits.push(c);
+            if !chars
+                .peek()
+                .is_some_and(|next| next.is_ascii_digit() || *next == '.' || *next == ',')
+            {
+                if !is_grouped(&digits) {
+                    return Err(invalid());
+                }
+                number = Some(digits.replace(',', "").parse().map_err(|_| invalid())?);
+                digits.clear();
+            }
+        } else if let Some(digit) = SINO_DIGITS.iter().position(|d| *d == c) {
+            if number.is_some() {
+                return Err(invalid());
+            }
+            number = Some(digit as f64);
+        } else if let Some((_, unit)) = SMALL_UNITS.iter().find(|(u, _)| *u == c) {
+            if last_small.is_some_and(|last| *unit >= last) {
+                return Err(invalid());
+            }
+            section += number.take().unwrap_or(1.0) * unit;
+            last_small = Some(*unit);
+            has_unit = true;
+        } else if let Some((_, unit)) = LARGE_UNITS.iter().find(|(u, _)| *u == c) {
+            if last_large.is_some_and(|last| *unit >= last) {
+                return Err(invalid());
+            }
+            match number.take() {
+                Some(number) => section += number,
+                // A unit on its own is one of it, e.g. 만 is 10,000, but not right after
+                // another one, e.g. 억만
+                None if section == 0.0 && last_large.is_none() => section = 1.0,
+                None if section == 0.0 => return Err(invalid()),
+                None => {}
+            }
+            total += section * unit;
+            section = 0.0;
+            last_large = Some(*unit);
+            last_small = None;
+            has_unit = true;
+        } else if !c.is_whitespace() {
+            return Err(invalid());
+        }
+    }
+
+    match (number, has_unit) {
+        (None, false) => Err(invalid()),
+        (number, _) => Ok(total + section + number.unwrap_or(0.0)),
+    }
+}
+
+/// Whether commas in the digits only separate groups of three, e.g. 10,000 but not 1,0,0
+fn is_grouped(digits: &str) -> bool {
+    if !digits.contains(',') {
+        return true;
+    }
+    let whole = digits.split('.').next().unwrap_or_default();
+    // No commas in the fraction
+    if digits[whole.len()..].contains(',') {
+        return false;
+    }
+    let mut groups = whole.split(',');
+    let first = groups.next().unwrap_or_default();
+    (1..=3).contains(&first.len()) && groups.all(|group| group.len() == 3)
+}
+
+/// Native Korean number words, tens followed by ones, e.g. 스물다섯
+fn parse_native(text: &str) -> Option<f64> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let (tens, rest) = NATIVE_TENS
+        .iter()
+        .find_map(|(word, tens)| text.strip_prefix(word).map(|rest| (*tens, rest)))
+        .unwrap_or((0.0, &text));
+    let ones = match rest {
+        "" if tens > 0.0 => 0.0,
+        rest => NATIVE_ONES
+            .iter()
+            .find(|(word, _)| *word == rest)
+            .map(|(_, ones)| *ones)?,
+    };
+    Some(tens + ones)
+}
+
+/// Writes a whole number of won in Korean units, grouping digits by 만 rather than by
+/// thousands, e.g. 1,234,500,000 becomes "12억 3,450만". Fractions are rounded
+/// # Examples
+/// ```
+/// use kd::korean::number::format_korean;
+/// assert_eq!(format_korean(1_234_500_000.0), "12억 3,450만");
+/// assert_eq!(format_korean(15_000.0), "1만 5,000");
+/// ```
+pub fn format_korean(amount: f64) -> String {
+    let mut rest = amount.abs().round() as u128;
+    if rest == 0 {
+        return "0".to_string();
+    }
+    let mut units = std::iter::once(String::new())
+        .chain(LARGE_UNITS.iter().map(|(unit, _)| unit.to_string()))
+        .peekable();
+    let mut groups: Vec<String> = Vec::new();
+    while let Some(unit) = units.next() {
+        // Whatever is left goes with the largest unit
+        let group = match units.peek() {
+            Some(_) => rest % 10_000,
+            None => rest,
+        };
+        rest /= 10_000;
+        if group > 0 {
+            groups.push(format!("{}{unit}", group.to_formatted_string(&Locale::en)));
+        }
+        if rest == 0 {
+            break;
+        }
+    }
+    groups.reverse();
+    let sign = if amount < 0.0 { "-" } else { "" };
+    format!("{sign}{}", groups.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_korean, parse_amount};
+
+    #[test]
+    fn should_parse_and_format_korean_numbers() {
+        let cases = [
+            ("10000000", 10_000_000.0),
+            ("10,000,000원", 10_000_000.0),
+            ("1,234.5", 1_234.5),
+            ("₩2.5", 2.5),
+            ("1.5억", 150_000_000.0),
+            ("3천만원", 30_000_000.0),
+            ("천만", 10_000_000.0),
+            ("1억 2천만", 120_000_000.0),
+            ("3천5백억", 350_000_000_000.0),
+            ("10만 5천", 105_000.0),
+            ("2조", 2e12),
+            ("삼천오백만", 35_000_000.0),
+            ("일억 이천만", 120_000_000.0),
+            ("구십", 90.0),
+            ("만원", 10_000.0),
+            ("일흔일곱", 77.0),
+            ("열", 10.0),
+        ];
+        for (text, amount) in cases {
+            assert_eq!(parse_amount(text).unwrap(), amount, "{text}");
+        }
+        assert!(parse_amount("").is_err());
+        assert!(parse_amount("1.2.3억").is_err());
+        assert!(parse_amount("three").is_err());
+        assert!(parse_amount("삼오").is_err());
+        for text in [
+            "만만",
+            "억만",
+            "1만2억",
+            "3천5천",
+            "백천",
+            "1,0,0",
+            ",100",
+            "1,000.0,0",
+        ] {
+            assert!(parse_amount(text).is_err(), "{text}");
+        }
+
+        assert_eq!(format_korean(1_234_500_000.0), "12억 3,450만");
+        assert_eq!(format_korean(100_000_000.0), "1억");
+        assert_eq!(format_korean(-9_999.4), "-9,999");
+        assert_eq!(format_korean(2e20), "20,000경");
+    }
+}
@@ -0,0 +1,255 @@
+//! Hangul to Revised Romanization of Korean
+//!
+//! Syllables are split into jamo, the sound changes between neighbouring syllables are
+//! applied and each jamo is then written with its Latin letters. Tensification isn't
+//! written in Revised Romanization and the exception for aspiration in nouns (묵호,
+//! Mukho) isn't applied.
+
+use super::jamo::Syllable;
+
+const INITIALS: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp", "s", "ss", "", "j", "jj", "ch", "k", "t", "p",
+    "h",
+];
+const VOWELS: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa", "wae", "oe", "yo", "u", "wo", "we",
+    "wi", "yu", "eu", "ui", "i",
+];
+/// Finals as pronounced at the end of a syllable
+const FINAL_SOUNDS: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k", "m", "l", "l", "l", "p", "l", "m", "p", "p",
+    "t", "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+// Initial consonants by index
+const G: usize = 0;
+const KK: usize = 1;
+const N: usize = 2;
+const D: usize = 3;
+const R: usize = 5;
+const M: usize = 6;
+const B: usize = 7;
+const S: usize = 9;
+const SS: usize = 10;
+const SILENT: usize = 11;
+const J: usize = 12;
+const CH: usize = 14;
+const K: usize = 15;
+const T: usize = 16;
+const P: usize = 17;
+const H: usize = 18;
+
+// Final consonants by index
+const NO_FINAL: usize = 0;
+const FINAL_G: usize = 1;
+const FINAL_N: usize = 4;
+const FINAL_NH: usize = 6;
+const FINAL_L: usize = 8;
+const FINAL_LH: usize = 15;
+const FINAL_M: usize = 16;
+const FINAL_B: usize = 17;
+const FINAL_NG: usize = 21;
+const FINAL_J: usize = 22;
+const FINAL_H: usize = 27;
+
+/// Vowel ㅣ, which palatalizes ㄷ and ㅌ moved onto it
+const VOWEL_I: usize = 20;
+
+/// What a final leaves behind and moves onto a following vowel, e.g. ㄺ keeps ㄹ and
+/// moves ㄱ. None for finals that don't move
+const LIAISON: [(usize, Option<usize>); 28] = [
+    (NO_FINAL, None),
+    (NO_FINAL, Some(G)),
+    (NO_FINAL, Some(KK)),
+    (FINAL_G, Some(S)),
+    (NO_FINAL, Some(N)),
+    (FINAL_N, Some(J)),
+    (NO_FINAL, Some(N)),
+    (NO_FINAL, Some(D)),
+    (NO_FINAL, Some(R)),
+    (FINAL_L, Some(G)),
+    (FINAL_L, Some(M)),
+    (FINAL_L, Some(B)),
+    (FINAL_L, Some(S)),
+    (FINAL_L, Some(T)),
+    (FINAL_L, Some(P)),
+    (NO_FINAL, Some(R)),
+    (NO_FINAL, Some(M)),
+    (NO_FINAL, Some(B)),
+    (FINAL_B, Some(S)),
+    (NO_FINAL, Some(S)),
+    (NO_FINAL, Some(SS)),
+    (FINAL_NG, None),
+    (NO_FINAL, Some(J)),
+    (NO_FINAL, Some(CH)),
+    (NO_FINAL, Some(K)),
+    (NO_FINAL, Some(T)),
+    (NO_FINAL, Some(P)),
+    (NO_FINAL, None),
+];
+
+fn final_sound(syllable: &Syllable) -> &'static str {
+    FINAL_SOUNDS[syllable.last]
+}
+
+/// Writes Hangul in the Latin alphabet following the Revised Romanization of Korean,
+/// e.g. "도깨비" becomes "dokkaebi". Anything that isn't a Hangul syllable is kept as is
+/// and sound changes only apply within a word
+/// # Examples
+/// ```
+/// use kd::korean::romanize::romanize;
+/// assert_eq!(romanize("사랑의 불시착"), "sarangui bulsichak");
+/// ```
+pub fn romanize(text: &str) -> String {
+    let mut romanized = String::new();
+    let mut word: Vec<Syllable> = Vec::new();
+    for c in text.chars() {
+        match Syllable::from_char(c) {
+            Some(syllable) => word.push(syllable),
+            None => {
+                romanized.push_str(&romanize_word(&mut word));
+                word.clear();
+                romanized.push(c);
+            }
+        }
+    }
+    romanized.push_str(&romanize_word(&mut word));
+    romanized
+}
+
+/// Same as [`romanize`] with the first letter of every word capitalized, as titles and
+/// names are written, e.g. "Sarangui Bulsichak"
+pub fn romanize_title(text: &str) -> String {
+    romanize(text)
+        .split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn romanize_word(word: &mut [Syllable]) -> String {
+    for i in 1..word.len() {
+        let (before, after) = word.split_at_mut(i);
+        change_sounds(&mut before[i - 1], &mut after[0]);
+    }
+
+    let mut romanized = String::new();
+    let mut previous: Option<Syllable> = None;
+    for syllable in word.iter() {
+        // ㄹㄹ is written ll rather than lr
+        let initial = match previous {
+            Some(previous) if syllable.initial == R && previous.last == FINAL_L => "l",
+            _ => INITIALS[syllable.initial],
+        };
+        romanized.push_str(initial);
+        romanized.push_str(VOWELS[syllable.vowel]);
+        romanized.push_str(final_sound(syllable));
+        previous = Some(*syllable);
+    }
+    romanized
+}
+
+/// Applies the sound changes between a syllable's final and the next syllable's initial
+fn change_sounds(first: &mut Syllable, second: &mut Syllable) {
+    let sound = final_sound(first);
+    match second.initial {
+        // Liaison, the final moves onto the following vowel
+        SILENT => {
+            let (rest, moved) = LIAISON[first.last];
+            if let Some(mut moved) = moved {
+                // Palatalization, ㄷ and ㅌ before 이 are pronounced ㅈ and ㅊ
+                if second.vowel == VOWEL_I {
+                    moved = match moved {
+                        D => J,
+                        T => CH,
+                        moved => moved,
+                    };
+                }
+                first.last = rest;
+                second.initial = moved;
+            } else if first.last == FINAL_H {
+                first.last = NO_FINAL;
+            }
+        }
+        // Aspiration, ㅎ combines with a neighbouring ㄱ, ㄷ, ㅂ or ㅈ
+        G | D | J | S if matches!(first.last, FINAL_H | FINAL_NH | FINAL_LH) => {
+            second.initial = match second.initial {
+                G => K,
+                D => T,
+                J => CH,
+                _ => SS,
+            };
+            first.last = match first.last {
+                FINAL_NH => FINAL_N,
+                FINAL_LH => FINAL_L,
+                _ => NO_FINAL,
+            };
+        }
+        H if matches!(sound, "k" | "t" | "p") => {
+            second.initial = match (sound, first.last) {
+                ("k", _) => K,
+                ("p", _) => P,
+                (_, FINAL_J) => CH,
+                _ => T,
+            };
+            first.last = LIAISON[first.last].0;
+        }
+        // ㄹ after any consonant but ㄹ and ㄴ is pronounced ㄴ, which then nasalizes
+        R if !matches!(sound, "" | "l" | "n") => {
+            second.initial = N;
+            nasalize(first);
+        }
+        // Liquidization, ㄴ next to ㄹ is pronounced ㄹ
+        R if sound == "n" => first.last = FINAL_L,
+        N if sound == "l" => second.initial = R,
+        // Nasalization, stops before ㄴ and ㅁ are pronounced as nasals
+        N | M => nasalize(first),
+        _ => {}
+    }
+}
+
+/// Turns a final stop into the nasal made in the same place, e.g. ㄱ into ㅇ
+fn nasalize(syllable: &mut Syllable) {
+    syllable.last = match final_sound(syllable) {
+        "k" => FINAL_NG,
+        "t" => FINAL_N,
+        "p" => FINAL_M,
+        _ => syllable.last,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{romanize, romanize_title};
+
+    #[test]
+    fn should_apply_sound_changes() {
+        let cases = [
+            ("도깨비", "dokkaebi"),
+            ("김치", "gimchi"),
+            ("한국어", "hangugeo"),
+            ("같이", "gachi"),
+            ("국물", "gungmul"),
+            ("합니다", "hamnida"),
+            ("종로", "jongno"),
+            ("독립", "dongnip"),
+            ("신라", "silla"),
+            ("설날", "seollal"),
+            ("좋고", "joko"),
+            ("좋아", "joa"),
+            ("축하", "chuka"),
+            ("읽어", "ilgeo"),
+            ("빛나", "binna"),
+        ];
+        for (hangul, romanized) in cases {
+            assert_eq!(romanize(hangul), romanized, "{hangul}");
+        }
+        assert_eq!(romanize_title("별에서 온 그대!"), "Byeoreseo On Geudae!");
+    }
+}
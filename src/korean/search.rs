@@ -0,0 +1,111 @@
+//! Matching names against what's typed so far, including Hangul being composed
+use super::jamo::{is_initial, Syllable};
+
+/// How well a name matches the input, best first
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum MatchQuality {
+    Exact,
+    Prefix,
+    Substring,
+    /// The name starts with the input's initial consonants or partial syllables
+    JamoPrefix,
+    /// The name contains the input's initial consonants or partial syllables
+    JamoSubstring,
+}
+
+/// Case-insensitive match of `input` against `name`. In Hangul a lone consonant matches
+/// any syllable starting with it, so "ㄷㄲㅂ" matches "도깨비", and a final syllable still
+/// being typed matches the syllables it's on its way to, so "돆" matches "도깨비"
+/// # Examples
+/// ```
+/// use kd::korean::search::{match_name, MatchQuality};
+/// assert_eq!(match_name("도깨비", "ㄷㄲ"), Some(MatchQuality::JamoPrefix));
+/// assert_eq!(match_name("Goblin", "gob"), Some(MatchQuality::Prefix));
+/// ```
+pub fn match_name(name: &str, input: &str) -> Option<MatchQuality> {
+    let name = name.to_lowercase();
+    let input = input.to_lowercase();
+    if name == input {
+        return Some(MatchQuality::Exact);
+    }
+    if name.starts_with(&input) {
+        return Some(MatchQuality::Prefix);
+    }
+    if name.contains(&input) {
+        return Some(MatchQuality::Substring);
+    }
+
+    let name: Vec<char> = name.chars().collect();
+    let input: Vec<char> = input.chars().collect();
+    let last_start = name.len().checked_sub(input.len())?;
+    (0..=last_start)
+        .find(|start| matches_at(&name, &input, *start))
+        .map(|start| match start {
+            0 => MatchQuality::JamoPrefix,
+            _ => MatchQuality::JamoSubstring,
+        })
+}
+
+/// Whether every input character matches the name from `start` on
+fn matches_at(name: &[char], input: &[char], start: usize) -> bool {
+    input.iter().enumerate().all(|(i, typed)| {
+        let c = name[start + i];
+        if *typed == c {
+            return true;
+        }
+        let syllable = match Syllable::from_char(c) {
+            Some(syllable) => syllable,
+            None => return false,
+        };
+        if is_initial(*typed) {
+            return syllable.get_initial() == *typed;
+        }
+        // Only the last syllable can still be being typed, and its final may turn out to
+        // be the next syllable's initial
+        match Syllable::from_char(*typed) {
+            Some(typed) if i + 1 == input.len() => {
+                let mut keys = syllable.get_keys();
+                if let Some(next) = name
+                    .get(start + i + 1)
+                    .copied()
+                    .and_then(Syllable::from_char)
+                {
+                    keys.push(next.get_initial());
+                }
+                keys.starts_with(&typed.get_keys())
+            }
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{match_name, MatchQuality};
+
+    #[test]
+    fn should_match_partial_hangul() {
+        assert_eq!(match_name("도깨비", "도깨비"), Some(MatchQuality::Exact));
+        assert_eq!(
+            match_name("도깨비", "ㄷㄲㅂ"),
+            Some(MatchQuality::JamoPrefix)
+        );
+        assert_eq!(
+            match_name("쓸쓸하고 찬란하神 도깨비", "ㄷㄲ"),
+            Some(MatchQuality::JamoSubstring)
+        );
+        assert_eq!(match_name("도깨비", "돆"), Some(MatchQuality::JamoPrefix));
+        assert_eq!(match_name("도깨비", "도ㄲ"), Some(MatchQuality::JamoPrefix));
+        assert_eq!(
+            match_name("사랑의 불시착", "사랑의 부"),
+            Some(MatchQuality::JamoPrefix)
+        );
+        assert_eq!(match_name("과거", "고"), Some(MatchQuality::JamoPrefix));
+        assert_eq!(match_name("도깨비", "ㄷㅅㅇ"), None);
+        assert_eq!(match_name("도깨비", "돋"), None);
+        assert_eq!(
+            match_name("Crash Landing on You", "landing"),
+            Some(MatchQuality::Substring)
+        );
+    }
+}
@@ -1,53 +1,141 @@
-use std::error::Error;
+use chrono::{Datelike, NaiveDate, Utc};
+use num_format::{Locale, ToFormattedString};
 
-use serde_json::Value;
+use crate::error::Error;
 
-/// Converts Korean Won to US Dollars
+use super::{
+    exchange::{ExchangeRateProvider, PriceIndexProvider, Rate},
+    number::format_korean,
+};
+
+/// Currencies without minor units, whole amounts are shown
+const WHOLE_CURRENCIES: [&str; 4] = ["KRW", "JPY", "VND", "IDR"];
+
+/// Converts `amount` of one currency to another at the rate `provider` gives for `date`,
+/// the latest rate when None, returning the converted amount and the rate used. A currency
+/// converts to itself at 1 without asking `provider`
 /// # Examples
 /// ```
-/// use kd::korean::utils;
-/// let usd = utils::krw_to_usd(10_000_000_000.0);
-pub fn krw_to_usd(won: f64) -> Result<f64, Box<dyn Error>> {
-    let forex_rate = get_krw_to_usd_forex_rate()?;
-    Ok(won * forex_rate)
+/// use kd::korean::{exchange::FixedRates, utils};
+/// let rates = FixedRates::new().with_rate("EUR", "KRW", 1500.0);
+/// let (eur, _) = utils::convert(15_000_000.0, "KRW", "EUR", None, &rates).unwrap();
+/// assert_eq!(eur, 10_000.0);
+/// ```
+pub fn convert(
+    amount: f64,
+    from: &str,
+    to: &str,
+    date: Option<NaiveDate>,
+    provider: &dyn ExchangeRateProvider,
+) -> Result<(f64, Rate), Error> {
+    if from.eq_ignore_ascii_case(to) {
+        let rate = Rate {
+            rate: 1.0,
+            as_of: Utc::now(),
+        };
+        return Ok((amount, rate));
+    }
+    let rate = provider.get_rate(from, to, date)?;
+    Ok((amount * rate.rate, rate))
 }
 
-pub fn get_krw_to_usd_forex_rate() -> Result<f64, Box<dyn Error>> {
-    let api_key = std::env::var("ALPHA_VANTAGE_API_KEY")?;
-    // Get JSON response text
-    let url = format!("https://www.alphavantage.co/query?function=CURRENCY_EXCHANGE_RATE&from_currency=KRW&to_currency=USD&apikey={}", api_key);
-    let body = reqwest::blocking::get(url)?.text()?;
-
-    // Parse JSON and get rate as string
-    let json: Value = serde_json::from_str(&body)?;
-    let rate = match json.get("Realtime Currency Exchange Rate") {
-        Some(value) => match value.get("5. Exchange Rate") {
-            Some(value) => match value.as_str() {
-                Some(value) => value,
-                None => return Err("none".into()),
-            },
-            None => return Err("Nope".into()),
-        },
-        None => {
-            println!("response: {body}");
-            return Err("Response not found".into())
-    },
-    };
+/// What `amount` of `currency` in `year` is worth today, going by the change in the
+/// consumer price index since
+/// # Examples
+/// ```
+/// use kd::korean::{exchange::FixedRates, utils};
+/// let indexes = FixedRates::new()
+///     .with_price_index("USD", 2016, 240.0)
+///     .with_price_index("USD", 2023, 300.0);
+/// let today = utils::adjust_for_inflation(1_000.0, "USD", 2016, &indexes).unwrap();
+/// assert_eq!(today, 1_250.0);
+/// ```
+pub fn adjust_for_inflation(
+    amount: f64,
+    currency: &str,
+    year: i32,
+    provider: &dyn PriceIndexProvider,
+) -> Result<f64, Error> {
+    let then = provider.get_price_index(currency, year)?;
+    let now = provider.get_price_index(currency, Utc::now().year())?;
+    Ok(amount * now / then)
+}
 
-    // Return as float
-    match rate.parse::<f64>() {
-        Ok(value) => Ok(value),
-        Err(_) => return Err("Not a number".into()),
+/// Converts Korean Won to US Dollars at the rate `provider` gives
+/// # Examples
+/// ```
+/// use kd::korean::{exchange::FixedRates, utils};
+/// let rates = FixedRates::new().with_rate("USD", "KRW", 1250.0);
+/// let usd = utils::krw_to_usd(10_000_000_000.0, &rates).unwrap();
+/// assert_eq!(usd, 8_000_000.0);
+/// ```
+pub fn krw_to_usd(won: f64, provider: &dyn ExchangeRateProvider) -> Result<f64, Error> {
+    convert(won, "KRW", "USD", None, provider).map(|(usd, _)| usd)
+}
+
+/// Locale numbers are formatted in, from `LC_ALL`, `LC_NUMERIC` or `LANG` such as
+/// en_US.UTF-8. English when none is set or known
+pub fn get_locale() -> Locale {
+    let name = ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    let name = name
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+    let language = name.split('-').next().unwrap_or_default();
+    Locale::from_name(&name)
+        .or_else(|_| Locale::from_name(language))
+        .unwrap_or(Locale::en)
+}
+
+/// Formats an amount of `currency`, won in Korean units and anything else with the
+/// locale's separators, e.g. "12억 3,450만" or "1.234,50" in German
+/// # Examples
+/// ```
+/// use kd::korean::utils::format_currency;
+/// use num_format::Locale;
+/// assert_eq!(format_currency(1_234.5, "USD", &Locale::en), "1,234.50");
+/// assert_eq!(format_currency(1_234.5, "EUR", &Locale::de), "1.234,50");
+/// ```
+pub fn format_currency(amount: f64, currency: &str, locale: &Locale) -> String {
+    if currency == "KRW" {
+        return format_korean(amount);
+    }
+    let decimals = match WHOLE_CURRENCIES.contains(&currency) {
+        true => 0,
+        false => 2,
+    };
+    let scale = 10_u128.pow(decimals);
+    let minor = (amount.abs() * scale as f64).round() as u128;
+    let sign = if amount < 0.0 && minor > 0 { "-" } else { "" };
+    let whole = (minor / scale).to_formatted_string(locale);
+    match decimals {
+        0 => format!("{sign}{whole}"),
+        _ => format!(
+            "{sign}{whole}{}{:0width$}",
+            locale.decimal(),
+            minor % scale,
+            width = decimals as usize
+        ),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::get_krw_to_usd_forex_rate;
+    use super::convert;
+    use crate::korean::exchange::AlphaVantage;
 
     #[test]
-    fn should_get_rate() {
-        let rate = get_krw_to_usd_forex_rate().expect("should be a number");
-        dbg!(rate);
+    fn should_convert_to_same_currency_without_provider() {
+        // Unreachable and without an API key, so any request would fail
+        let provider = AlphaVantage::new("http://127.0.0.1:1", None);
+        let (amount, rate) = convert(1_000.0, "USD", "usd", None, &provider).unwrap();
+        assert_eq!(amount, 1_000.0);
+        assert_eq!(rate.rate, 1.0);
+        assert!(convert(1_000.0, "USD", "KRW", None, &provider).is_err());
     }
 }
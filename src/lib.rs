@@ -1,10 +1,17 @@
 //! # kd library
 //! Base business logic for the kd project
-pub mod korean;
-pub mod music;
 pub mod config;
+pub mod error;
+pub mod korean;
 pub mod models;
+pub mod music;
+pub mod settings;
+pub mod storage;
+#[cfg(test)]
+mod testing;
+
+pub use error::{Error, Result};
 
 pub trait DisplayMoreInfo {
     fn more_info(&self) -> String;
-}
\ No newline at end of file
+}
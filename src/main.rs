@@ -1,11 +1,31 @@
 //!  # kd - Korean Drama
 //!
 //! kd helps easily document Korean Dramas making watching Korean Dramas more fun!
-use clap::{Parser, Subcommand};
-use kd::{config, korean::utils, models::actor::Actor};
-use std::process;
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
+use controllers::{
+    episode::EpisodeFields, korean::ConvertFields, views::ConfigView, Context, MetadataFields,
+    MoneyFields, NameFields,
+};
+use kd::{
+    config,
+    models::{
+        query::{CharacterSort, Filter, ShowSort},
+        watch::WatchStatus,
+    },
+    settings::Settings,
+    storage::Backend,
+};
+use output::OutputFormat;
+use std::{
+    env,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    process,
+};
 
 pub mod controllers;
+pub mod output;
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -16,18 +36,78 @@ struct Cli {
 
     #[clap(global = true, short, long, action = clap::ArgAction::Count)]
     verbose: u8,
+
+    /// Output format: text, json, yaml or table. Defaults to the format setting
+    #[clap(global = true, long, value_parser)]
+    format: Option<OutputFormat>,
+
+    /// Answer yes to every confirmation
+    #[clap(global = true, short, long)]
+    yes: bool,
+
+    /// Never prompt, missing values are reported as errors. Implied when stdin isn't a terminal
+    #[clap(global = true, long)]
+    no_input: bool,
+
+    /// Directory the data is stored in [env: KD_DATA_DIR]
+    #[clap(global = true, long, value_parser)]
+    data_dir: Option<PathBuf>,
+
+    /// Directory the config is read from [env: KD_CONFIG_DIR]
+    #[clap(global = true, long, value_parser)]
+    config_dir: Option<PathBuf>,
+
+    /// Named library to use instead of the default one [env: KD_LIBRARY]
+    #[clap(global = true, short = 'L', long)]
+    library: Option<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// View config info
-    Config,
+    /// View config info and manage settings
+    Config {
+        #[clap(subcommand)]
+        command: Option<ConfigCommands>,
+    },
 
     /// Convert currencies
     Convert {
-        /// Korean Won to convert to USD
+        /// Amount to convert, digits or Korean units such as 1.5억 or 3천만원
         #[clap(value_parser)]
-        won: f64,
+        amount: Option<String>,
+
+        /// Currency the amount is in, KRW when not given
+        #[clap(long, value_parser, conflicts_with_all = &["budget", "fee"])]
+        from: Option<String>,
+
+        /// Currency to convert to. Defaults to the currency setting
+        #[clap(long, value_parser)]
+        to: Option<String>,
+
+        /// Convert at the rate on this day, as YYYY-MM-DD, and adjust amounts in USD for
+        /// inflation since
+        #[clap(long, value_parser)]
+        date: Option<NaiveDate>,
+
+        /// Units of the target currency per unit of the source to convert at instead of
+        /// the exchange rate
+        #[clap(long, value_parser)]
+        rate: Option<f64>,
+
+        /// Convert the budget of this show, at the rate of the day it was quoted
+        #[clap(long, value_parser, conflicts_with_all = &["amount", "fee"])]
+        budget: Option<String>,
+
+        /// Convert the fee of this actor, at the rate of the day it was quoted
+        #[clap(long, value_parser, conflicts_with = "amount")]
+        fee: Option<String>,
+    },
+
+    /// Write Hangul in the Latin alphabet using the Revised Romanization of Korean
+    Romanize {
+        /// Hangul to romanize
+        #[clap(value_parser)]
+        text: Option<String>,
     },
 
     /// Interact with shows
@@ -42,11 +122,107 @@ enum Commands {
         command: Option<CharacterCommands>,
     },
 
+    /// Interact with the seasons of a show
+    Season {
+        #[clap(subcommand)]
+        command: Option<SeasonCommands>,
+    },
+
+    /// Interact with the episodes of a show
+    Episode {
+        #[clap(subcommand)]
+        command: Option<EpisodeCommands>,
+    },
+
     /// Interact with actors
     Actor {
         #[clap(subcommand)]
         command: Option<ActorCommands>,
     },
+
+    /// Search show names, character names, relationship kinds and show details
+    Search {
+        /// Text to look for
+        #[clap(value_parser)]
+        term: String,
+
+        #[clap(flatten)]
+        filter: FilterArgs,
+    },
+
+    /// Track how far along each show is
+    Watch {
+        #[clap(subcommand)]
+        command: WatchCommands,
+    },
+
+    /// Score a show, or one of its episodes with --episode
+    Rate {
+        /// Name of show
+        #[clap(value_parser)]
+        show_name: Option<String>,
+
+        /// Score from 1 to 10
+        #[clap(value_parser)]
+        score: Option<u8>,
+
+        /// Season of the episode, needed when the show has several
+        #[clap(short, long, value_parser)]
+        season: Option<u16>,
+
+        /// Rate this episode instead of the show
+        #[clap(short, long, value_parser)]
+        episode: Option<u16>,
+    },
+
+    /// Write a review of a show, or one of its episodes with --episode
+    Review {
+        /// Name of show
+        #[clap(value_parser)]
+        show_name: Option<String>,
+
+        /// Text of the review, replaces any earlier review of yours
+        #[clap(short, long)]
+        text: Option<String>,
+
+        /// Season of the episode, needed when the show has several
+        #[clap(short, long, value_parser)]
+        season: Option<u16>,
+
+        /// Review this episode instead of the show
+        #[clap(short, long, value_parser)]
+        episode: Option<u16>,
+    },
+
+    /// List shows by score, or the episodes of a show with --show-name
+    Rank {
+        /// Rank the episodes of this show
+        #[clap(long)]
+        show_name: Option<String>,
+
+        /// Number of entries to show
+        #[clap(short, long, value_parser)]
+        limit: Option<usize>,
+    },
+
+    /// Revert the most recent change
+    Undo,
+
+    /// Reapply the most recently undone change
+    Redo,
+
+    /// List recorded changes, most recent first
+    History {
+        /// Number of entries to show
+        #[clap(short, long, value_parser, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Maintain the data file
+    Data {
+        #[clap(subcommand)]
+        command: DataCommands,
+    },
 }
 
 #[derive(Subcommand)]
@@ -60,6 +236,15 @@ enum ShowCommands {
         /// Release year of show
         #[clap(short, long, required(false))]
         release_year: Option<i16>,
+
+        #[clap(flatten)]
+        names: NameArgs,
+
+        #[clap(flatten)]
+        budget: BudgetArgs,
+
+        #[clap(flatten)]
+        metadata: MetadataArgs,
     },
 
     /// Updae show
@@ -75,6 +260,15 @@ enum ShowCommands {
         /// Release year of show
         #[clap(short, long, required(false))]
         release_year: Option<i16>,
+
+        #[clap(flatten)]
+        names: NameArgs,
+
+        #[clap(flatten)]
+        budget: BudgetArgs,
+
+        #[clap(flatten)]
+        metadata: MetadataArgs,
     },
 
     /// Show info on show
@@ -83,12 +277,33 @@ enum ShowCommands {
         #[clap(short, long, required(false))]
         name: Option<String>,
     },
+
+    /// Delete show along with its characters and relationships
+    Delete {
+        /// Name of show
+        #[clap(short, long, required(false))]
+        name: Option<String>,
+    },
+
+    /// List shows
+    List {
+        #[clap(flatten)]
+        filter: FilterArgs,
+
+        /// Sort by name, year or characters
+        #[clap(long, value_parser, default_value = "name")]
+        sort: ShowSort,
+    },
 }
 
 #[derive(Subcommand)]
 enum CharacterCommands {
     /// Add character
     Add {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
         /// Name of character
         #[clap(short, long, required(false))]
         name: Option<String>,
@@ -100,10 +315,17 @@ enum CharacterCommands {
         /// Gender of character
         #[clap(short, long, required(false))]
         gender: Option<String>,
+
+        #[clap(flatten)]
+        names: NameArgs,
     },
 
     /// Update character
     Update {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
         /// Old name of character
         #[clap(short, long, required(false))]
         old_name: Option<String>,
@@ -119,6 +341,9 @@ enum CharacterCommands {
         /// Gender of character
         #[clap(short, long, required(false))]
         gender: Option<String>,
+
+        #[clap(flatten)]
+        names: NameArgs,
     },
 
     /// Set relationship
@@ -138,98 +363,772 @@ enum CharacterCommands {
         /// Relationship type
         #[clap(short, long, required(false))]
         relationship_name: Option<String>,
+
+        /// Delete the relationship instead of setting it
+        #[clap(long)]
+        delete: bool,
+    },
+
+    /// Delete character along with its relationships
+    Delete {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
+        /// Name of character
+        #[clap(short, long, required(false))]
+        name: Option<String>,
+    },
+
+    /// List characters across shows
+    List {
+        #[clap(flatten)]
+        filter: FilterArgs,
+
+        /// Sort by name, show, role or gender
+        #[clap(long, value_parser, default_value = "name")]
+        sort: CharacterSort,
+    },
+}
+
+/// Filters shared by the list commands
+#[derive(Args)]
+struct FilterArgs {
+    /// Only include this show
+    #[clap(long, required(false))]
+    show_name: Option<String>,
+
+    /// Earliest release year
+    #[clap(long, required(false))]
+    from_year: Option<i16>,
+
+    /// Latest release year
+    #[clap(long, required(false))]
+    to_year: Option<i16>,
+
+    /// Character role
+    #[clap(long, required(false))]
+    role: Option<String>,
+
+    /// Character gender
+    #[clap(long, required(false))]
+    gender: Option<String>,
+
+    /// Relationship kind a character takes part in
+    #[clap(long, required(false))]
+    relationship: Option<String>,
+
+    /// Network that aired the show
+    #[clap(long, required(false))]
+    network: Option<String>,
+
+    /// Platform streaming the show
+    #[clap(long, required(false))]
+    platform: Option<String>,
+
+    /// Genre of the show
+    #[clap(long, required(false))]
+    genre: Option<String>,
+
+    /// Tag on the show
+    #[clap(long, required(false))]
+    tag: Option<String>,
+}
+
+impl From<FilterArgs> for Filter {
+    fn from(args: FilterArgs) -> Self {
+        Filter {
+            show_name: args.show_name,
+            from_year: args.from_year,
+            to_year: args.to_year,
+            role: args.role,
+            gender: args.gender,
+            relationship: args.relationship,
+            network: args.network,
+            platform: args.platform,
+            genre: args.genre,
+            tag: args.tag,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum SeasonCommands {
+    /// Add season
+    Add {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
+        /// Season number, defaults to the one after the latest season
+        #[clap(short, long, required(false))]
+        number: Option<u16>,
+
+        /// Title of season
+        #[clap(short, long, required(false))]
+        title: Option<String>,
+    },
+
+    /// Edit season
+    Edit {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
+        /// Season number
+        #[clap(short, long, required(false))]
+        number: Option<u16>,
+
+        /// Title of season, empty to unset
+        #[clap(short, long, required(false))]
+        title: Option<String>,
+    },
+
+    /// List the seasons of a show
+    List {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum EpisodeCommands {
+    /// Add episode
+    Add {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
+        /// Season number, defaults to the latest season
+        #[clap(short, long, required(false))]
+        season: Option<u16>,
+
+        /// Episode number, defaults to the one after the latest episode of the season
+        #[clap(short, long, required(false))]
+        number: Option<u16>,
+
+        #[clap(flatten)]
+        fields: EpisodeArgs,
+    },
+
+    /// Edit episode
+    Edit {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
+        /// Season number
+        #[clap(short, long, required(false))]
+        season: Option<u16>,
+
+        /// Episode number
+        #[clap(short, long, required(false))]
+        number: Option<u16>,
+
+        #[clap(flatten)]
+        fields: EpisodeArgs,
+    },
+
+    /// List the episodes of a show
+    List {
+        /// Show name
+        #[clap(long, required(false))]
+        show_name: Option<String>,
+
+        /// Only include this season
+        #[clap(short, long, required(false))]
+        season: Option<u16>,
+    },
+}
+
+/// Other names of a show or character, which lookups match as well as the name
+#[derive(Args)]
+struct NameArgs {
+    /// Name in Hangul, e.g. 도깨비, empty to unset
+    #[clap(long, required(false))]
+    hangul: Option<String>,
+
+    /// Romanized name, e.g. Dokkaebi, empty to unset
+    #[clap(long, required(false))]
+    romanized: Option<String>,
+
+    /// English name, repeat for several. Replaces the English names, empty to unset
+    #[clap(long, required(false), multiple_occurrences(true))]
+    english: Vec<String>,
+}
+
+impl From<NameArgs> for NameFields {
+    fn from(args: NameArgs) -> Self {
+        NameFields {
+            hangul: args.hangul,
+            romanized: args.romanized,
+            english: Some(args.english).filter(|english| !english.is_empty()),
+        }
+    }
+}
+
+/// Production budget of a show
+#[derive(Args)]
+struct BudgetArgs {
+    /// Budget, digits or Korean units such as 300억, empty to unset
+    #[clap(long, required(false))]
+    budget: Option<String>,
+
+    /// Currency of the budget, KRW when not given
+    #[clap(long, required(false))]
+    budget_currency: Option<String>,
+
+    /// Day the budget was quoted, as YYYY-MM-DD
+    #[clap(long, value_parser, required(false))]
+    budget_date: Option<NaiveDate>,
+}
+
+impl From<BudgetArgs> for MoneyFields {
+    fn from(args: BudgetArgs) -> Self {
+        MoneyFields {
+            amount: args.budget,
+            currency: args.budget_currency,
+            date: args.budget_date,
+        }
+    }
+}
+
+/// Production details of a show
+#[derive(Args)]
+struct MetadataArgs {
+    /// Network that aired the show, e.g. tvN, empty to unset
+    #[clap(long, required(false))]
+    network: Option<String>,
+
+    /// Platform streaming the show, repeat for several. Replaces the platforms, empty to unset
+    #[clap(long, required(false), multiple_occurrences(true))]
+    platform: Vec<String>,
+
+    /// Genre, repeat for several. Replaces the genres, empty to unset
+    #[clap(long, required(false), multiple_occurrences(true))]
+    genre: Vec<String>,
+
+    /// Tag, repeat for several. Replaces the tags, empty to unset
+    #[clap(long, required(false), multiple_occurrences(true))]
+    tag: Vec<String>,
+}
+
+impl From<MetadataArgs> for MetadataFields {
+    fn from(args: MetadataArgs) -> Self {
+        let given = |values: Vec<String>| Some(values).filter(|values| !values.is_empty());
+        MetadataFields {
+            network: args.network,
+            platforms: given(args.platform),
+            genres: given(args.genre),
+            tags: given(args.tag),
+        }
+    }
+}
+
+/// Fee per episode of an actor
+#[derive(Args)]
+struct FeeArgs {
+    /// Fee per episode, digits or Korean units such as 1억, empty to unset
+    #[clap(long, required(false))]
+    fee: Option<String>,
+
+    /// Currency of the fee, KRW when not given
+    #[clap(long, required(false))]
+    fee_currency: Option<String>,
+
+    /// Day the fee was quoted, as YYYY-MM-DD
+    #[clap(long, value_parser, required(false))]
+    fee_date: Option<NaiveDate>,
+}
+
+impl From<FeeArgs> for MoneyFields {
+    fn from(args: FeeArgs) -> Self {
+        MoneyFields {
+            amount: args.fee,
+            currency: args.fee_currency,
+            date: args.fee_date,
+        }
+    }
+}
+
+/// Episode details shared by add and edit
+#[derive(Args)]
+struct EpisodeArgs {
+    /// Title of episode, empty to unset
+    #[clap(short, long, required(false))]
+    title: Option<String>,
+
+    /// Date the episode first aired, as YYYY-MM-DD
+    #[clap(long, value_parser, required(false))]
+    air_date: Option<NaiveDate>,
+
+    /// Length in minutes
+    #[clap(long, value_parser, required(false))]
+    runtime: Option<u16>,
+
+    /// Summary of what happens, empty to unset
+    #[clap(long, required(false))]
+    synopsis: Option<String>,
+
+    /// Your own notes on the episode, empty to unset
+    #[clap(long, required(false))]
+    notes: Option<String>,
+}
+
+impl From<EpisodeArgs> for EpisodeFields {
+    fn from(args: EpisodeArgs) -> Self {
+        EpisodeFields {
+            title: args.title,
+            air_date: args.air_date,
+            runtime: args.runtime,
+            synopsis: args.synopsis,
+            notes: args.notes,
+        }
     }
 }
 
+#[derive(Subcommand)]
+enum WatchCommands {
+    /// Mark the next episode of a show as watched
+    Next {
+        /// Name of show
+        #[clap(value_parser)]
+        show_name: Option<String>,
+    },
+
+    /// List shows by watch status, most recently watched first
+    Status {
+        /// Status to list: plan-to-watch, watching, completed, dropped or on-hold
+        #[clap(long, value_parser, default_value = "watching")]
+        status: WatchStatus,
+    },
+
+    /// Set the watch status of a show
+    Set {
+        /// Name of show
+        #[clap(value_parser)]
+        show_name: Option<String>,
+
+        /// New status: plan-to-watch, watching, completed, dropped or on-hold
+        #[clap(short, long, value_parser)]
+        status: Option<WatchStatus>,
+    },
+}
+
 #[derive(Subcommand)]
 enum ActorCommands {
     /// Add actor
     Add {
         /// Name of actor
-        #[clap(short, long)]
-        name: String,
+        #[clap(short, long, required(false))]
+        name: Option<String>,
 
         /// Birth year
-        #[clap(short, long)]
-        birth_year: i64,
+        #[clap(short, long, required(false))]
+        birth_year: Option<i64>,
+
+        #[clap(flatten)]
+        fee: FeeArgs,
+    },
+
+    /// Update actor
+    Update {
+        /// Current name of actor
+        #[clap(short, long, required(false))]
+        old_name: Option<String>,
+
+        /// New name of actor
+        #[clap(short, long, required(false))]
+        new_name: Option<String>,
+
+        /// Birth year
+        #[clap(short, long, required(false))]
+        birth_year: Option<i64>,
+
+        #[clap(flatten)]
+        fee: FeeArgs,
+    },
+
+    /// Cast actor as a character in a show
+    Cast {
+        /// Name of actor
+        #[clap(short, long, required(false))]
+        name: Option<String>,
+
+        /// Show name
+        #[clap(short, long, required(false))]
+        show_name: Option<String>,
+
+        /// Name of the character the actor plays
+        #[clap(short, long, required(false))]
+        character_name: Option<String>,
+    },
+
+    /// Show info and filmography of actor
+    Info {
+        /// Name of actor
+        #[clap(short, long, required(false))]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the value of a setting
+    Get {
+        #[clap(value_parser)]
+        key: String,
+    },
+
+    /// Change a setting, lists are comma separated
+    Set {
+        #[clap(value_parser)]
+        key: String,
+
+        #[clap(value_parser)]
+        value: String,
+    },
+
+    /// List every setting
+    List,
+
+    /// Open config.toml in the configured editor
+    Edit,
+}
+
+#[derive(Subcommand)]
+enum DataCommands {
+    /// Find and fix relationships that point at missing characters
+    Repair {
+        /// Only report orphaned relationships
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Delete orphaned relationships instead of prompting for replacements
+        #[clap(long)]
+        delete_orphans: bool,
+    },
+
+    /// Upgrade the data file to the current schema version, keeping a backup
+    Migrate {
+        /// Only report what would change
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    /// Move the data to another storage backend
+    Convert {
+        /// Backend to convert to: json or sqlite
+        #[clap(long, value_parser)]
+        to: Backend,
     },
 }
-// fn format_float(input: f64) -> String {
-//     let input: String = format!("")
-//     input
-// }
 
 fn main() {
     let cli = Cli::parse();
+    // Until the settings are loaded errors use the format from the command line
+    let format = cli.format.unwrap_or(OutputFormat::Text);
 
-    let config = config::Config::new();
-    if let Err(e) = config.init() {
-        eprintln!("{e}");
-        process::exit(1);
+    // Flags win over the environment. Everything reads the locations through Config, so
+    // setting the variables here applies the flags everywhere
+    if let Some(data_dir) = &cli.data_dir {
+        env::set_var(config::DATA_DIR_VAR, data_dir);
+    }
+    if let Some(config_dir) = &cli.config_dir {
+        env::set_var(config::CONFIG_DIR_VAR, config_dir);
+    }
+    if let Some(library) = &cli.library {
+        env::set_var(config::LIBRARY_VAR, library);
     }
 
     // Optionally load env variables from config .env file
-    let env_file = config.get_config_dir().join(".env");
-    if let Ok(_) = dotenv::from_path(env_file.as_path()) {
-        if cli.verbose > 0 {
-            println!("Loaded from .env file");
-        }
+    let env_file = config::Config::new().get_config_dir().join(".env");
+    if dotenv::from_path(env_file.as_path()).is_ok() && cli.verbose > 0 {
+        eprintln!("Loaded from .env file");
     }
 
-    match cli.command {
-        Some(Commands::Config) => {
-            println!(
-                "Config directory: {:?}",
-                config.get_config_dir().as_os_str()
-            );
-            println!("Data directory: {:?}", config.get_data_dir().as_os_str());
+    // The .env file may have changed the locations
+    let config = config::Config::new();
+    if let Err(e) = config.init() {
+        output::print_error(format, &e);
+        process::exit(e.exit_code());
+    }
+    let settings = match Settings::load(config.get_config_dir()) {
+        Ok(settings) => settings,
+        Err(e) => {
+            output::print_error(format, &e);
+            process::exit(e.exit_code());
         }
-        Some(Commands::Convert { won }) => {
-            println!("Converting {} won to usd...", won);
-            match utils::krw_to_usd(won) {
-                Ok(usd) => {
-                    println!("{} won = {} usd", won, usd);
+    };
+    let format = match cli.format {
+        Some(format) => format,
+        None => settings.format.parse().unwrap_or(OutputFormat::Text),
+    };
+    let ctx = Context {
+        format,
+        assume_yes: cli.yes,
+        no_input: cli.no_input || !io::stdin().is_terminal(),
+        settings,
+    };
+
+    let result = match cli.command {
+        Some(Commands::Config { command }) => match command {
+            Some(ConfigCommands::Get { key }) => controllers::config::get_controller(&ctx, key),
+            Some(ConfigCommands::Set { key, value }) => {
+                controllers::config::set_controller(&ctx, &config, key, value)
+            }
+            Some(ConfigCommands::List) => controllers::config::list_controller(&ctx),
+            Some(ConfigCommands::Edit) => controllers::config::edit_controller(&ctx, &config),
+            None => ctx.print(&ConfigView {
+                config_dir: config.get_config_dir().display().to_string(),
+                data_dir: config.get_data_dir().display().to_string(),
+                library: config.get_library().to_string(),
+                libraries: config.get_libraries(),
+            }),
+        },
+        Some(Commands::Convert {
+            amount,
+            from,
+            to,
+            date,
+            rate,
+            budget,
+            fee,
+        }) => {
+            if cli.verbose > 0 {
+                if let Some(amount) = &amount {
+                    eprintln!(
+                        "Converting {} {}...",
+                        amount,
+                        from.as_deref().unwrap_or("KRW")
+                    );
                 }
-                Err(e) => println!("{e}"),
+            }
+            let fields = ConvertFields {
+                amount,
+                from,
+                to,
+                date,
+                rate,
+                budget,
+                fee,
             };
+            controllers::korean::convert_controller(&ctx, &config, fields)
         }
+        Some(Commands::Romanize { text }) => controllers::korean::romanize_controller(&ctx, text),
         Some(Commands::Show { command }) => match command {
-            Some(ShowCommands::Add { name, release_year }) => {
-                controllers::show::add_show_controller(name, release_year)
-            }
+            Some(ShowCommands::Add {
+                name,
+                release_year,
+                names,
+                budget,
+                metadata,
+            }) => controllers::show::add_show_controller(
+                &ctx,
+                name,
+                release_year,
+                names.into(),
+                budget.into(),
+                metadata.into(),
+            ),
             Some(ShowCommands::Update {
                 old_name,
                 new_name,
                 release_year,
-            }) => controllers::show::update_show_controller(old_name, new_name, release_year),
-            Some(ShowCommands::Info { name }) => controllers::show::display_more_info(name),
-            None => {}
+                names,
+                budget,
+                metadata,
+            }) => controllers::show::update_show_controller(
+                &ctx,
+                old_name,
+                new_name,
+                release_year,
+                names.into(),
+                budget.into(),
+                metadata.into(),
+            ),
+            Some(ShowCommands::Info { name }) => controllers::show::display_more_info(&ctx, name),
+            Some(ShowCommands::Delete { name }) => {
+                controllers::show::delete_show_controller(&ctx, name)
+            }
+            Some(ShowCommands::List { filter, sort }) => {
+                controllers::show::list_shows_controller(&ctx, filter.into(), sort)
+            }
+            None => Ok(()),
         },
         Some(Commands::Character { command }) => match command {
-            Some(CharacterCommands::Add { name, role, gender }) => {
-                controllers::character::add_character_controller(name, role, gender);
-            }
+            Some(CharacterCommands::Add {
+                show_name,
+                name,
+                role,
+                gender,
+                names,
+            }) => controllers::character::add_character_controller(
+                &ctx,
+                show_name,
+                name,
+                role,
+                gender,
+                names.into(),
+            ),
             Some(CharacterCommands::Update {
+                show_name,
                 old_name,
                 new_name,
                 role,
                 gender,
-            }) => {
-                controllers::character::update_character_controller(
-                    old_name, new_name, role, gender,
-                );
-            },
-            Some(CharacterCommands::Rel { show_name, source_name, target_name, relationship_name }) =>
-                controllers::show::set_relationship_controller(show_name, source_name, target_name, relationship_name),
-            None => {}
+                names,
+            }) => controllers::character::update_character_controller(
+                &ctx,
+                show_name,
+                old_name,
+                new_name,
+                role,
+                gender,
+                names.into(),
+            ),
+            Some(CharacterCommands::Rel {
+                show_name,
+                source_name,
+                target_name,
+                relationship_name,
+                delete,
+            }) => controllers::show::set_relationship_controller(
+                &ctx,
+                show_name,
+                source_name,
+                target_name,
+                relationship_name,
+                delete,
+            ),
+            Some(CharacterCommands::Delete { show_name, name }) => {
+                controllers::character::delete_character_controller(&ctx, show_name, name)
+            }
+            Some(CharacterCommands::List { filter, sort }) => {
+                controllers::character::list_characters_controller(&ctx, filter.into(), sort)
+            }
+            None => Ok(()),
+        },
+        Some(Commands::Season { command }) => match command {
+            Some(SeasonCommands::Add {
+                show_name,
+                number,
+                title,
+            }) => controllers::episode::add_season_controller(&ctx, show_name, number, title),
+            Some(SeasonCommands::Edit {
+                show_name,
+                number,
+                title,
+            }) => controllers::episode::edit_season_controller(&ctx, show_name, number, title),
+            Some(SeasonCommands::List { show_name }) => {
+                controllers::episode::list_seasons_controller(&ctx, show_name)
+            }
+            None => Ok(()),
+        },
+        Some(Commands::Episode { command }) => match command {
+            Some(EpisodeCommands::Add {
+                show_name,
+                season,
+                number,
+                fields,
+            }) => controllers::episode::add_episode_controller(
+                &ctx,
+                show_name,
+                season,
+                number,
+                fields.into(),
+            ),
+            Some(EpisodeCommands::Edit {
+                show_name,
+                season,
+                number,
+                fields,
+            }) => controllers::episode::edit_episode_controller(
+                &ctx,
+                show_name,
+                season,
+                number,
+                fields.into(),
+            ),
+            Some(EpisodeCommands::List { show_name, season }) => {
+                controllers::episode::list_episodes_controller(&ctx, show_name, season)
+            }
+            None => Ok(()),
         },
         Some(Commands::Actor { command }) => match command {
-            Some(ActorCommands::Add { name, birth_year }) => {
-                let actor = Actor::new(name, birth_year);
-                println!("Added new actor: {actor}");
+            Some(ActorCommands::Add {
+                name,
+                birth_year,
+                fee,
+            }) => controllers::actor::add_actor_controller(&ctx, name, birth_year, fee.into()),
+            Some(ActorCommands::Update {
+                old_name,
+                new_name,
+                birth_year,
+                fee,
+            }) => controllers::actor::update_actor_controller(
+                &ctx,
+                old_name,
+                new_name,
+                birth_year,
+                fee.into(),
+            ),
+            Some(ActorCommands::Cast {
+                name,
+                show_name,
+                character_name,
+            }) => controllers::actor::cast_actor_controller(&ctx, name, show_name, character_name),
+            Some(ActorCommands::Info { name }) => {
+                controllers::actor::display_actor_info(&ctx, name)
+            }
+            None => Ok(()),
+        },
+        Some(Commands::Search { term, filter }) => {
+            controllers::search::search_controller(&ctx, term, filter.into())
+        }
+        Some(Commands::Watch { command }) => match command {
+            WatchCommands::Next { show_name } => {
+                controllers::watch::next_controller(&ctx, show_name)
+            }
+            WatchCommands::Status { status } => controllers::watch::status_controller(&ctx, status),
+            WatchCommands::Set { show_name, status } => {
+                controllers::watch::set_controller(&ctx, show_name, status)
             }
-            None => {}
         },
-        None => {}
+        Some(Commands::Rate {
+            show_name,
+            score,
+            season,
+            episode,
+        }) => controllers::review::rate_controller(&ctx, show_name, score, season, episode),
+        Some(Commands::Review {
+            show_name,
+            text,
+            season,
+            episode,
+        }) => controllers::review::review_controller(&ctx, show_name, text, season, episode),
+        Some(Commands::Rank { show_name, limit }) => {
+            controllers::review::rank_controller(&ctx, show_name, limit)
+        }
+        Some(Commands::Undo) => controllers::history::undo_controller(&ctx),
+        Some(Commands::Redo) => controllers::history::redo_controller(&ctx),
+        Some(Commands::History { limit }) => controllers::history::history_controller(&ctx, limit),
+        Some(Commands::Data { command }) => match command {
+            DataCommands::Repair {
+                dry_run,
+                delete_orphans,
+            } => controllers::data::repair_controller(&ctx, dry_run, delete_orphans),
+            DataCommands::Migrate { dry_run } => {
+                controllers::data::migrate_controller(&ctx, dry_run)
+            }
+            DataCommands::Convert { to } => controllers::data::convert_controller(&ctx, to),
+        },
+        None => Ok(()),
+    };
+
+    if let Err(e) = result {
+        output::print_error(ctx.format, &e);
+        process::exit(e.exit_code());
     }
 }
@@ -1,23 +1,42 @@
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::money::Money;
 
 /// Represents an actor in a show
+#[derive(Serialize, Deserialize)]
 pub struct Actor {
-    name: String,
-    birth_year: i64
+    pub id: String,
+    pub name: String,
+    pub birth_year: i64,
+    /// Fee per episode
+    #[serde(default)]
+    pub fee: Option<Money>,
 }
 
 impl Actor {
-    pub fn new(name: String, birth_year: i64)  -> Actor{
+    pub fn new(name: String, birth_year: i64) -> Actor {
         Actor {
+            id: Uuid::new_v4().to_string(),
             name,
-            birth_year
+            birth_year,
+            fee: None,
         }
     }
+
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name == name
+    }
 }
 
 impl Display for Actor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} - born {}", self.name, self.birth_year)
+        write!(f, "{} - born {}", self.name, self.birth_year)?;
+        if let Some(fee) = &self.fee {
+            write!(f, ", fee {fee}")?;
+        }
+        Ok(())
     }
-}
\ No newline at end of file
+}
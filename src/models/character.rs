@@ -3,13 +3,21 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::names::{NameVariant, Names};
+
 /// Represents a character in a show
 #[derive(Serialize, Deserialize)]
 pub struct Character {
     pub id: String,
     pub name: String,
+    /// Hangul, romanized and English names, which lookups match as well
+    #[serde(default)]
+    pub names: Names,
     pub role: String,
     pub gender: String,
+    /// Id of the actor cast as this character
+    #[serde(default)]
+    pub actor_id: Option<String>,
 }
 
 impl Character {
@@ -17,10 +25,21 @@ impl Character {
         Character {
             id: Uuid::new_v4().to_string(),
             name,
+            names: Names::default(),
             role,
             gender,
+            actor_id: None,
         }
     }
+
+    /// Whether `name` is the character's name or one of its other names
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name == name || self.names.contains(name)
+    }
+
+    pub fn get_display_name(&self, variant: NameVariant) -> &str {
+        self.names.display(&self.name, variant)
+    }
 }
 
 impl Display for Character {
@@ -0,0 +1,109 @@
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::review::Reviews;
+
+/// A season of a show and its episodes, ordered by number
+#[derive(Serialize, Deserialize)]
+pub struct Season {
+    pub number: u16,
+    #[serde(default)]
+    pub title: Option<String>,
+    pub episodes: Vec<Episode>,
+}
+
+impl Season {
+    pub fn new(number: u16, title: Option<String>) -> Season {
+        Season {
+            number,
+            title,
+            episodes: Vec::new(),
+        }
+    }
+
+    /// Total runtime in minutes of the episodes whose runtime is known
+    pub fn get_runtime(&self) -> u32 {
+        self.episodes
+            .iter()
+            .filter_map(|e| e.runtime)
+            .map(u32::from)
+            .sum()
+    }
+}
+
+impl Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Season {}", self.number)?;
+        if let Some(title) = &self.title {
+            write!(f, ": {title}")?;
+        }
+        write!(f, " ({} episodes)", self.episodes.len())
+    }
+}
+
+/// Represents an episode of a season
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Episode {
+    pub id: String,
+    pub number: u16,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub air_date: Option<NaiveDate>,
+    /// Length in minutes
+    #[serde(default)]
+    pub runtime: Option<u16>,
+    #[serde(default)]
+    pub synopsis: Option<String>,
+    /// The user's own notes on the episode
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub reviews: Reviews,
+}
+
+impl Episode {
+    pub fn new(number: u16) -> Episode {
+        Episode {
+            id: Uuid::new_v4().to_string(),
+            number,
+            title: None,
+            air_date: None,
+            runtime: None,
+            synopsis: None,
+            notes: None,
+            reviews: Reviews::default(),
+        }
+    }
+}
+
+impl Display for Episode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Episode {}", self.number)?;
+        if let Some(title) = &self.title {
+            write!(f, ": {title}")?;
+        }
+        let details: Vec<String> = [
+            self.air_date.map(|date| date.to_string()),
+            self.runtime.map(|runtime| format!("{runtime} min")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Minutes as hours and minutes, e.g. "17h 30m"
+pub fn format_runtime(minutes: u32) -> String {
+    match minutes / 60 {
+        0 => format!("{minutes}m"),
+        hours => format!("{hours}h {}m", minutes % 60),
+    }
+}
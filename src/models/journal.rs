@@ -0,0 +1,399 @@
+//! Append-only log of every change to the collection, used for undo, redo and history
+use std::{collections::HashMap, fs::OpenOptions, io::Write, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    config::{self, Config},
+    error::Error,
+};
+
+use super::show_collection::ShowCollection;
+
+pub const FILE_NAME: &str = "journal.jsonl";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Change,
+    Undo,
+    Redo,
+}
+
+/// Kind of record a [`Change`] applies to
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordKind {
+    Show,
+    Actor,
+}
+
+/// A record as it was before and after a change. A missing side means the record was
+/// added or deleted
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Change {
+    pub record: RecordKind,
+    pub id: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl Change {
+    /// E.g. "Updated show Goblin"
+    pub fn describe(&self) -> String {
+        let action = match (&self.before, &self.after) {
+            (None, _) => "Added",
+            (_, None) => "Deleted",
+            _ => "Updated",
+        };
+        let record = match self.record {
+            RecordKind::Show => "show",
+            RecordKind::Actor => "actor",
+        };
+        let name = self
+            .after
+            .as_ref()
+            .or(self.before.as_ref())
+            .and_then(|value| value.get("name"))
+            .and_then(Value::as_str)
+            .unwrap_or(&self.id);
+        format!("{action} {record} {name}")
+    }
+
+    fn reversed(&self) -> Change {
+        Change {
+            record: self.record,
+            id: self.id.clone(),
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Entry {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Login of whoever ran kd
+    pub user: String,
+    pub kind: EntryKind,
+    /// Id of the entry an undo or redo applies to
+    #[serde(default)]
+    pub target: Option<String>,
+    pub changes: Vec<Change>,
+}
+
+impl Entry {
+    pub fn new(kind: EntryKind, target: Option<String>, changes: Vec<Change>) -> Entry {
+        Entry {
+            id: Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            user: config::get_user(),
+            kind,
+            target,
+            changes,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        self.changes
+            .iter()
+            .map(Change::describe)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The journal file, one JSON [`Entry`] per line
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(path: PathBuf) -> Journal {
+        Journal { path }
+    }
+
+    /// The journal next to the data file
+    pub fn open() -> Journal {
+        Journal::new(Config::new().get_data_dir().join(FILE_NAME))
+    }
+
+    pub fn read(&self) -> Result<Vec<Entry>, Error> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        let mut entries = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(line).map_err(|e| {
+                Error::from(e).context(format!("Invalid journal entry on line {}", index + 1))
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    pub fn append(&self, entry: &Entry) -> Result<(), Error> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| {
+                Error::from(e).context(format!("Unable to open journal {:?}", self.path))
+            })?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+/// Entries that can currently be undone and redone, most recent last
+pub fn get_stacks(entries: &[Entry]) -> (Vec<&Entry>, Vec<&Entry>) {
+    let mut done: Vec<&Entry> = Vec::new();
+    let mut undone: Vec<&Entry> = Vec::new();
+    let by_id: HashMap<&str, &Entry> = entries.iter().map(|e| (e.id.as_str(), e)).collect();
+    for entry in entries {
+        match entry.kind {
+            EntryKind::Change => {
+                done.push(entry);
+                undone.clear();
+            }
+            EntryKind::Undo => {
+                done.retain(|e| Some(&e.id) != entry.target.as_ref());
+                if let Some(target) = entry.target.as_deref().and_then(|id| by_id.get(id)) {
+                    undone.push(target);
+                }
+            }
+            EntryKind::Redo => {
+                undone.retain(|e| Some(&e.id) != entry.target.as_ref());
+                if let Some(target) = entry.target.as_deref().and_then(|id| by_id.get(id)) {
+                    done.push(target);
+                }
+            }
+        }
+    }
+    (done, undone)
+}
+
+/// Serialized shows and actors, compared at save to find what changed
+#[derive(Default)]
+pub(crate) struct Snapshot {
+    shows: HashMap<String, Value>,
+    actors: HashMap<String, Value>,
+}
+
+impl Snapshot {
+    pub(crate) fn take(show_collection: &ShowCollection) -> Result<Snapshot, serde_json::Error> {
+        Ok(Snapshot {
+            shows: to_values(&show_collection.shows)?,
+            actors: to_values(&show_collection.actors)?,
+        })
+    }
+
+    /// Changes that turn this snapshot into `other`
+    pub(crate) fn diff(&self, other: &Snapshot) -> Vec<Change> {
+        let mut changes = diff_records(RecordKind::Show, &self.shows, &other.shows);
+        changes.extend(diff_records(RecordKind::Actor, &self.actors, &other.actors));
+        changes
+    }
+}
+
+fn to_values<T: Serialize>(
+    records: &HashMap<String, T>,
+) -> Result<HashMap<String, Value>, serde_json::Error> {
+    records
+        .iter()
+        .map(|(id, record)| Ok((id.clone(), serde_json::to_value(record)?)))
+        .collect()
+}
+
+fn diff_records(
+    record: RecordKind,
+    before: &HashMap<String, Value>,
+    after: &HashMap<String, Value>,
+) -> Vec<Change> {
+    let mut ids: Vec<&String> = before.keys().chain(after.keys()).collect();
+    ids.sort();
+    ids.dedup();
+    ids.into_iter()
+        .filter(|id| before.get(*id) != after.get(*id))
+        .map(|id| Change {
+            record,
+            id: id.clone(),
+            before: before.get(id).cloned(),
+            after: after.get(id).cloned(),
+        })
+        .collect()
+}
+
+impl ShowCollection {
+    /// Reverts the most recent change that hasn't been undone yet
+    pub fn undo() -> Result<Entry, Error> {
+        // Lock before reading the journal so no other instance appends to it meanwhile
+        let show_collection = Self::load()?;
+        let journal = Journal::open();
+        let entries = journal.read()?;
+        let (done, _) = get_stacks(&entries);
+        let target = done
+            .last()
+            .ok_or_else(|| Error::Validation("Nothing to undo".to_string()))?;
+        let changes: Vec<Change> = target.changes.iter().rev().map(Change::reversed).collect();
+        show_collection.replay(&journal, EntryKind::Undo, target, changes)
+    }
+
+    /// Reapplies the most recently undone change
+    pub fn redo() -> Result<Entry, Error> {
+        let show_collection = Self::load()?;
+        let journal = Journal::open();
+        let entries = journal.read()?;
+        let (_, undone) = get_stacks(&entries);
+        let target = undone
+            .last()
+            .ok_or_else(|| Error::Validation("Nothing to redo".to_string()))?;
+        show_collection.replay(&journal, EntryKind::Redo, target, target.changes.clone())
+    }
+
+    /// Journal entries, most recent first
+    pub fn history() -> Result<Vec<Entry>, Error> {
+        let mut entries = Journal::open().read()?;
+        entries.reverse();
+        Ok(entries)
+    }
+
+    fn replay(
+        mut self,
+        journal: &Journal,
+        kind: EntryKind,
+        target: &Entry,
+        changes: Vec<Change>,
+    ) -> Result<Entry, Error> {
+        for change in changes.iter() {
+            match change.record {
+                RecordKind::Show => apply(&mut self.shows, change)?,
+                RecordKind::Actor => apply(&mut self.actors, change)?,
+            }
+        }
+        self.write()?;
+        let entry = Entry::new(kind, Some(target.id.clone()), changes);
+        journal.append(&entry)?;
+        Ok(entry)
+    }
+}
+
+/// Sets the record to `change.after`, refusing if it no longer matches `change.before`
+fn apply<T: Serialize + DeserializeOwned>(
+    records: &mut HashMap<String, T>,
+    change: &Change,
+) -> Result<(), Error> {
+    let current = match records.get(&change.id) {
+        Some(record) => Some(serde_json::to_value(record)?),
+        None => None,
+    };
+    // Snapshots from before a schema change lack the newer fields, compare them the way
+    // they'd be written today
+    if current != normalize::<T>(&change.before)? {
+        return Err(Error::Conflict(format!(
+            "Can't apply \"{}\", it has been changed since",
+            change.describe()
+        )));
+    }
+    match &change.after {
+        Some(after) => {
+            records.insert(change.id.clone(), serde_json::from_value(after.clone())?);
+        }
+        None => {
+            records.remove(&change.id);
+        }
+    }
+    Ok(())
+}
+
+/// Reads a stored snapshot as a record and writes it back, filling in defaults for fields
+/// added since it was stored
+fn normalize<T: Serialize + DeserializeOwned>(
+    value: &Option<Value>,
+) -> Result<Option<Value>, Error> {
+    match value {
+        Some(value) => {
+            let record: T = serde_json::from_value(value.clone())?;
+            Ok(Some(serde_json::to_value(record)?))
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::models::show::Show;
+
+    use super::{apply, get_stacks, Change, Entry, EntryKind, RecordKind};
+
+    fn entry(kind: EntryKind, target: Option<&Entry>) -> Entry {
+        Entry::new(kind, target.map(|e| e.id.clone()), Vec::new())
+    }
+
+    #[test]
+    fn should_track_undo_and_redo_stacks() {
+        let first = entry(EntryKind::Change, None);
+        let second = entry(EntryKind::Change, None);
+        let undo = entry(EntryKind::Undo, Some(&second));
+        let mut entries = vec![first, second, undo];
+        let (done, undone) = get_stacks(&entries);
+        assert_eq!(done.len(), 1);
+        assert_eq!(undone[0].id, entries[1].id);
+
+        let redo = entry(EntryKind::Redo, Some(&entries[1]));
+        entries.push(redo);
+        let (done, undone) = get_stacks(&entries);
+        assert_eq!(done.len(), 2);
+        assert!(undone.is_empty());
+
+        // A new change after an undo drops what could be redone
+        let undo = entry(EntryKind::Undo, Some(&entries[1]));
+        entries.push(undo);
+        entries.push(entry(EntryKind::Change, None));
+        let (done, undone) = get_stacks(&entries);
+        assert_eq!(done.len(), 2);
+        assert!(undone.is_empty());
+    }
+
+    #[test]
+    fn should_undo_changes_stored_before_a_schema_change() {
+        let show = Show::new("Goblin".to_string(), 2016);
+        let id = show.id.clone();
+        let mut before = serde_json::to_value(&show).unwrap();
+        // As written before show metadata existed
+        for key in ["network", "platforms", "genres", "tags", "budget"] {
+            before.as_object_mut().unwrap().remove(key);
+        }
+        let mut shows = HashMap::from([(id.clone(), show)]);
+
+        let mut changed = before.clone();
+        changed["name"] = "Dokkaebi".into();
+        let conflict = Change {
+            record: RecordKind::Show,
+            id: id.clone(),
+            before: Some(changed),
+            after: None,
+        };
+        assert!(apply(&mut shows, &conflict).is_err());
+
+        let change = Change {
+            record: RecordKind::Show,
+            id: id.clone(),
+            before: Some(before),
+            after: None,
+        };
+        apply(&mut shows, &change).unwrap();
+        assert!(shows.is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+//! Upgrades of the data file from older schema versions
+use std::path::PathBuf;
+
+use serde_json::{json, Map, Value};
+
+use crate::error::Error;
+
+/// Schema version written by this build of kd
+pub const CURRENT_VERSION: u32 = 7;
+
+/// Upgrades a file from `to - 1` to `to`, returning a description of each change made
+struct Migration {
+    to: u32,
+    apply: fn(&mut Map<String, Value>) -> Vec<String>,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        to: 1,
+        apply: add_actors,
+    },
+    Migration {
+        to: 2,
+        apply: add_seasons,
+    },
+    Migration {
+        to: 3,
+        apply: add_watch_progress,
+    },
+    Migration {
+        to: 4,
+        apply: add_reviews,
+    },
+    Migration {
+        to: 5,
+        apply: add_names,
+    },
+    Migration {
+        to: 6,
+        apply: add_money,
+    },
+    Migration {
+        to: 7,
+        apply: add_show_metadata,
+    },
+];
+
+/// What a migration did, or would do when run as a dry run
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub changes: Vec<String>,
+    /// Copy of the file from before the migration
+    pub backup: Option<PathBuf>,
+}
+
+impl MigrationReport {
+    pub fn is_needed(&self) -> bool {
+        self.from_version != self.to_version
+    }
+}
+
+/// Version of a data file, files from before versioning count as version 0
+pub fn get_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+/// Runs every migration newer than the file's version on `value`
+pub fn migrate(value: &mut Value) -> Result<MigrationReport, Error> {
+    let from_version = get_version(value);
+    if from_version > CURRENT_VERSION {
+        return Err(Error::Parse(format!(
+            "Data file has schema version {from_version} but this kd only understands up to {CURRENT_VERSION}, please upgrade kd"
+        )));
+    }
+    let root = value
+        .as_object_mut()
+        .ok_or_else(|| Error::Parse("Data file isn't a JSON object".to_string()))?;
+
+    let mut changes = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.to > from_version) {
+        changes.extend((migration.apply)(root));
+        root.insert("version".to_string(), Value::from(migration.to));
+    }
+    Ok(MigrationReport {
+        from_version,
+        to_version: CURRENT_VERSION,
+        changes,
+        backup: None,
+    })
+}
+
+/// Version 1 introduced actors and casting
+fn add_actors(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut changes = Vec::new();
+    if !root.contains_key("actors") {
+        root.insert("actors".to_string(), Value::Object(Map::new()));
+        changes.push("Added empty actor list".to_string());
+    }
+
+    let mut uncast = 0;
+    let shows = root.get_mut("shows").and_then(Value::as_object_mut);
+    for show in shows.into_iter().flat_map(|shows| shows.values_mut()) {
+        let characters = show.get_mut("characters").and_then(Value::as_array_mut);
+        for character in characters.into_iter().flatten() {
+            if let Some(character) = character.as_object_mut() {
+                if !character.contains_key("actor_id") {
+                    character.insert("actor_id".to_string(), Value::Null);
+                    uncast += 1;
+                }
+            }
+        }
+    }
+    if uncast > 0 {
+        changes.push(format!("Added missing actor to {uncast} character(s)"));
+    }
+    changes
+}
+
+/// Version 2 introduced seasons and episodes
+fn add_seasons(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut count = 0;
+    let shows = root.get_mut("shows").and_then(Value::as_object_mut);
+    for show in shows.into_iter().flat_map(|shows| shows.values_mut()) {
+        if let Some(show) = show.as_object_mut() {
+            if !show.contains_key("seasons") {
+                show.insert("seasons".to_string(), Value::Array(Vec::new()));
+                count += 1;
+            }
+        }
+    }
+    match count {
+        0 => Vec::new(),
+        count => vec![format!("Added empty season list to {count} show(s)")],
+    }
+}
+
+/// Version 3 introduced watch progress, untracked shows have none
+fn add_watch_progress(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut count = 0;
+    let shows = root.get_mut("shows").and_then(Value::as_object_mut);
+    for show in shows.into_iter().flat_map(|shows| shows.values_mut()) {
+        if let Some(show) = show.as_object_mut() {
+            if !show.contains_key("watch") {
+                show.insert("watch".to_string(), Value::Null);
+                count += 1;
+            }
+        }
+    }
+    match count {
+        0 => Vec::new(),
+        count => vec![format!("Added empty watch progress to {count} show(s)")],
+    }
+}
+
+/// Version 4 introduced reviews of shows and episodes
+fn add_reviews(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut count = 0;
+    let mut add = |record: &mut Value| {
+        if let Some(record) = record.as_object_mut() {
+            if !record.contains_key("reviews") {
+                record.insert("reviews".to_string(), Value::Array(Vec::new()));
+                count += 1;
+            }
+        }
+    };
+    let shows = root.get_mut("shows").and_then(Value::as_object_mut);
+    for show in shows.into_iter().flat_map(|shows| shows.values_mut()) {
+        let seasons = show.get_mut("seasons").and_then(Value::as_array_mut);
+        for season in seasons.into_iter().flatten() {
+            let episodes = season.get_mut("episodes").and_then(Value::as_array_mut);
+            episodes.into_iter().flatten().for_each(&mut add);
+        }
+        add(show);
+    }
+    match count {
+        0 => Vec::new(),
+        count => vec![format!(
+            "Added empty review list to {count} show(s) and episode(s)"
+        )],
+    }
+}
+
+/// Version 5 introduced Hangul, romanized and English names of shows and characters
+fn add_names(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut count = 0;
+    let mut add = |record: &mut Value| {
+        if let Some(record) = record.as_object_mut() {
+            if !record.contains_key("names") {
+                let names = json!({"hangul": null, "romanized": null, "english": []});
+                record.insert("names".to_string(), names);
+                count += 1;
+            }
+        }
+    };
+    let shows = root.get_mut("shows").and_then(Value::as_object_mut);
+    for show in shows.into_iter().flat_map(|shows| shows.values_mut()) {
+        let characters = show.get_mut("characters").and_then(Value::as_array_mut);
+        characters.into_iter().flatten().for_each(&mut add);
+        add(show);
+    }
+    match count {
+        0 => Vec::new(),
+        count => vec![format!(
+            "Added empty other names to {count} show(s) and character(s)"
+        )],
+    }
+}
+
+/// Version 6 introduced show budgets and actor fees, unknown ones are empty
+fn add_money(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut count = 0;
+    let mut add = |record: &mut Value, key: &str| {
+        if let Some(record) = record.as_object_mut() {
+            if !record.contains_key(key) {
+                record.insert(key.to_string(), Value::Null);
+                count += 1;
+            }
+        }
+    };
+    let shows = root.get_mut("shows").and_then(Value::as_object_mut);
+    for show in shows.into_iter().flat_map(|shows| shows.values_mut()) {
+        add(show, "budget");
+    }
+    let actors = root.get_mut("actors").and_then(Value::as_object_mut);
+    for actor in actors.into_iter().flat_map(|actors| actors.values_mut()) {
+        add(actor, "fee");
+    }
+    match count {
+        0 => Vec::new(),
+        count => vec![format!(
+            "Added empty budget or fee to {count} show(s) and actor(s)"
+        )],
+    }
+}
+
+/// Version 7 introduced the network, platforms, genres and tags of shows
+fn add_show_metadata(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut count = 0;
+    let shows = root.get_mut("shows").and_then(Value::as_object_mut);
+    for show in shows.into_iter().flat_map(|shows| shows.values_mut()) {
+        if let Some(show) = show.as_object_mut() {
+            if !show.contains_key("network") {
+                show.insert("network".to_string(), Value::Null);
+                for key in ["platforms", "genres", "tags"] {
+                    show.insert(key.to_string(), Value::Array(Vec::new()));
+                }
+                count += 1;
+            }
+        }
+    }
+    match count {
+        0 => Vec::new(),
+        count => vec![format!("Added empty production details to {count} show(s)")],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{migrate, CURRENT_VERSION};
+
+    #[test]
+    fn should_migrate_unversioned_file() {
+        let mut value = json!({
+            "shows": {
+                "1": {
+                    "id": "1",
+                    "name": "Goblin",
+                    "release_year": 2016,
+                    "characters": [
+                        {"id": "2", "name": "Kim Shin", "role": "protagonist", "gender": "male"}
+                    ],
+                    "relationships": {}
+                }
+            }
+        });
+        let report = migrate(&mut value).unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.changes.len(), 8);
+        assert_eq!(value["version"], CURRENT_VERSION);
+        assert!(value["actors"].is_object());
+        assert!(value["shows"]["1"]["characters"][0]["actor_id"].is_null());
+        assert!(value["shows"]["1"]["seasons"].is_array());
+        assert!(value["shows"]["1"]["watch"].is_null());
+        assert!(value["shows"]["1"]["reviews"].is_array());
+        assert!(value["shows"]["1"]["characters"][0]["names"]["english"].is_array());
+        assert!(value["shows"]["1"]["budget"].is_null());
+        assert!(value["shows"]["1"]["genres"].is_array());
+
+        // Running again is a no-op
+        let report = migrate(&mut value).unwrap();
+        assert!(!report.is_needed());
+        assert!(report.changes.is_empty());
+
+        value["version"] = json!(CURRENT_VERSION + 1);
+        assert!(migrate(&mut value).is_err());
+    }
+}
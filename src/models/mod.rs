@@ -1,4 +1,12 @@
 pub mod actor;
-pub mod show;
 pub mod character;
-pub mod show_collection;
\ No newline at end of file
+pub mod episode;
+pub mod journal;
+pub mod migration;
+pub mod money;
+pub mod names;
+pub mod query;
+pub mod review;
+pub mod show;
+pub mod show_collection;
+pub mod watch;
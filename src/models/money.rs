@@ -0,0 +1,50 @@
+//! Amounts of money in the currency and on the date they were quoted, such as show budgets
+use std::fmt::Display;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    korean::{
+        exchange::parse_currency,
+        utils::{format_currency, get_locale},
+    },
+};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Money {
+    pub amount: f64,
+    /// ISO 4217 code, e.g. KRW
+    pub currency: String,
+    /// When the amount was quoted, conversions use the rate of that day. None converts at
+    /// the latest rate
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+}
+
+impl Money {
+    pub fn new(amount: f64, currency: &str, date: Option<NaiveDate>) -> Result<Money, Error> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(Error::Validation(format!(
+                "{amount} isn't an amount of money"
+            )));
+        }
+        Ok(Money {
+            amount,
+            currency: parse_currency(currency)?,
+            date,
+        })
+    }
+}
+
+impl Display for Money {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let amount = format_currency(self.amount, &self.currency, &get_locale());
+        write!(f, "{amount} {}", self.currency)?;
+        if let Some(date) = self.date {
+            write!(f, " as of {date}")?;
+        }
+        Ok(())
+    }
+}
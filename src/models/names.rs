@@ -0,0 +1,165 @@
+//! The Hangul, romanized and English names of shows and characters
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, korean::romanize::romanize_title};
+
+/// Which name is displayed, set with the `names` setting. Falls back to the name a show
+/// or character was added with when the variant isn't known
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NameVariant {
+    /// The name it was added with
+    #[default]
+    Name,
+    Hangul,
+    Romanized,
+    /// The first English name
+    English,
+}
+
+impl Display for NameVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let variant = match self {
+            NameVariant::Name => "name",
+            NameVariant::Hangul => "hangul",
+            NameVariant::Romanized => "romanized",
+            NameVariant::English => "english",
+        };
+        write!(f, "{variant}")
+    }
+}
+
+impl FromStr for NameVariant {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(NameVariant::Name),
+            "hangul" => Ok(NameVariant::Hangul),
+            "romanized" => Ok(NameVariant::Romanized),
+            "english" => Ok(NameVariant::English),
+            _ => Err(Error::Validation(format!(
+                "Unknown name variant '{s}', expected name, hangul, romanized or english"
+            ))),
+        }
+    }
+}
+
+/// Other names a show or character is known by
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+pub struct Names {
+    /// Original title in Hangul, e.g. 도깨비
+    #[serde(default)]
+    pub hangul: Option<String>,
+    /// Hangul in the Latin alphabet, e.g. Dokkaebi
+    #[serde(default)]
+    pub romanized: Option<String>,
+    /// English titles, the first is the one displayed
+    #[serde(default)]
+    pub english: Vec<String>,
+}
+
+impl Names {
+    pub fn get(&self, variant: NameVariant) -> Option<&str> {
+        match variant {
+            NameVariant::Name => None,
+            NameVariant::Hangul => self.hangul.as_deref(),
+            NameVariant::Romanized => self.romanized.as_deref(),
+            NameVariant::English => self.english.first().map(String::as_str),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.hangul
+            .iter()
+            .chain(self.romanized.iter())
+            .chain(self.english.iter())
+            .map(String::as_str)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.iter().any(|n| n == name)
+    }
+
+    /// The variant, or `name` when it isn't known
+    pub fn display<'a>(&'a self, name: &'a str, variant: NameVariant) -> &'a str {
+        self.get(variant).unwrap_or(name)
+    }
+
+    /// Every name but the displayed one, for "Also known as" lines
+    pub fn get_others<'a>(&'a self, name: &'a str, variant: NameVariant) -> Vec<&'a str> {
+        let shown = self.display(name, variant);
+        let mut others: Vec<&str> = Vec::new();
+        for other in std::iter::once(name).chain(self.iter()) {
+            if other != shown && !others.contains(&other) {
+                others.push(other);
+            }
+        }
+        others
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Sets the variants given, empty ones are unset. A new Hangul name also sets the
+    /// romanized name to its romanization unless one is given alongside it
+    pub fn update(
+        &mut self,
+        hangul: Option<String>,
+        romanized: Option<String>,
+        english: Option<Vec<String>>,
+    ) {
+        let non_empty = |value: String| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        let hangul = hangul.map(non_empty);
+        match (romanized, &hangul) {
+            (Some(romanized), _) => self.romanized = non_empty(romanized),
+            (None, Some(Some(hangul))) => self.romanized = Some(romanize_title(hangul)),
+            (None, _) => {}
+        }
+        if let Some(hangul) = hangul {
+            self.hangul = hangul;
+        }
+        if let Some(english) = english {
+            self.english = english.into_iter().filter_map(non_empty).collect();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NameVariant, Names};
+
+    #[test]
+    fn should_fall_back_when_variant_is_unset() {
+        let mut names = Names::default();
+        names.update(
+            Some("도깨비".to_string()),
+            Some(" ".to_string()),
+            Some(vec!["Guardian: The Lonely and Great God".to_string()]),
+        );
+        assert_eq!(names.get(NameVariant::Hangul), Some("도깨비"));
+        assert_eq!(names.get(NameVariant::Romanized), None);
+        assert!(names.contains("Guardian: The Lonely and Great God"));
+        assert_eq!(names.iter().count(), 2);
+        assert_eq!(names.display("Goblin", NameVariant::Romanized), "Goblin");
+        assert_eq!(
+            names.get_others("Goblin", NameVariant::Hangul),
+            vec!["Goblin", "Guardian: The Lonely and Great God"]
+        );
+        assert!("Romanized".parse::<NameVariant>().unwrap() == NameVariant::Romanized);
+
+        // Unrelated updates leave an unset romanized name unset
+        names.update(None, None, Some(vec!["Goblin".to_string()]));
+        assert_eq!(names.get(NameVariant::Romanized), None);
+
+        names.update(Some("사랑".to_string()), None, None);
+        assert_eq!(names.get(NameVariant::Romanized), Some("Sarang"));
+        names.update(Some("도깨비".to_string()), None, None);
+        assert_eq!(names.get(NameVariant::Romanized), Some("Dokkaebi"));
+        names.update(Some("사랑".to_string()), Some("Love".to_string()), None);
+        assert_eq!(names.get(NameVariant::Romanized), Some("Love"));
+    }
+}
@@ -0,0 +1,378 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::error::Error;
+
+use super::{
+    character::Character,
+    show::{Relationship, Show},
+    show_collection::ShowCollection,
+};
+
+/// Criteria shared by show and character listings. Unset fields match everything
+#[derive(Default)]
+pub struct Filter {
+    pub show_name: Option<String>,
+    pub from_year: Option<i16>,
+    pub to_year: Option<i16>,
+    pub role: Option<String>,
+    pub gender: Option<String>,
+    pub relationship: Option<String>,
+    pub network: Option<String>,
+    /// Streaming service the show is on
+    pub platform: Option<String>,
+    pub genre: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl Filter {
+    fn matches_show(&self, show: &Show) -> bool {
+        if let Some(show_name) = &self.show_name {
+            let matches = std::iter::once(show.name.as_str())
+                .chain(show.names.iter())
+                .any(|name| name.eq_ignore_ascii_case(show_name));
+            if !matches {
+                return false;
+            }
+        }
+        if self.from_year.is_some_and(|year| show.release_year < year)
+            || self.to_year.is_some_and(|year| show.release_year > year)
+        {
+            return false;
+        }
+        if let Some(network) = &self.network {
+            if !show
+                .network
+                .as_ref()
+                .is_some_and(|n| n.eq_ignore_ascii_case(network))
+            {
+                return false;
+            }
+        }
+        for (wanted, values) in [
+            (&self.platform, &show.platforms),
+            (&self.genre, &show.genres),
+            (&self.tag, &show.tags),
+        ] {
+            if let Some(wanted) = wanted {
+                if !values
+                    .iter()
+                    .any(|value| value.eq_ignore_ascii_case(wanted))
+                {
+                    return false;
+                }
+            }
+        }
+        // A show matches character criteria if any of its characters does
+        if self.role.is_some() || self.gender.is_some() || self.relationship.is_some() {
+            return show
+                .characters
+                .iter()
+                .any(|character| self.matches_character(show, character));
+        }
+        true
+    }
+
+    fn matches_character(&self, show: &Show, character: &Character) -> bool {
+        if let Some(role) = &self.role {
+            if !character.role.eq_ignore_ascii_case(role) {
+                return false;
+            }
+        }
+        if let Some(gender) = &self.gender {
+            if !character.gender.eq_ignore_ascii_case(gender) {
+                return false;
+            }
+        }
+        if let Some(kind) = &self.relationship {
+            return show
+                .get_character_relationships(&character.id)
+                .iter()
+                .any(|r| r.kind.eq_ignore_ascii_case(kind));
+        }
+        true
+    }
+}
+
+/// Order of `show list`
+#[derive(Clone, Copy)]
+pub enum ShowSort {
+    Name,
+    Year,
+    Characters,
+}
+
+/// Order of `character list`
+#[derive(Clone, Copy)]
+pub enum CharacterSort {
+    Name,
+    Show,
+    Role,
+    Gender,
+}
+
+/// A match found by [`ShowCollection::search`]
+pub enum SearchResult<'a> {
+    Show(&'a Show),
+    Character(&'a Show, &'a Character),
+    Relationship(&'a Show, &'a Relationship),
+}
+
+impl Display for SearchResult<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchResult::Show(show) => write!(f, "Show: {show}"),
+            SearchResult::Character(show, character) => {
+                write!(f, "Character: {character} in {}", show.name)
+            }
+            SearchResult::Relationship(show, relationship) => write!(
+                f,
+                "Relationship: {} in {}",
+                show.describe_relationship(relationship),
+                show.name
+            ),
+        }
+    }
+}
+
+impl ShowCollection {
+    pub fn list_shows(&self, filter: &Filter, sort: ShowSort) -> Vec<&Show> {
+        let mut shows: Vec<&Show> = self
+            .shows
+            .values()
+            .filter(|show| filter.matches_show(show))
+            .collect();
+        shows.sort_by(|a, b| match sort {
+            ShowSort::Name => a.name.cmp(&b.name),
+            ShowSort::Year => a
+                .release_year
+                .cmp(&b.release_year)
+                .then_with(|| a.name.cmp(&b.name)),
+            ShowSort::Characters => b
+                .characters
+                .len()
+                .cmp(&a.characters.len())
+                .then_with(|| a.name.cmp(&b.name)),
+        });
+        shows
+    }
+
+    pub fn list_characters(
+        &self,
+        filter: &Filter,
+        sort: CharacterSort,
+    ) -> Vec<(&Show, &Character)> {
+        let mut characters: Vec<(&Show, &Character)> = self
+            .shows
+            .values()
+            .filter(|show| {
+                // Only the show-level criteria apply here, characters are checked below
+                let show_filter = Filter {
+                    show_name: filter.show_name.clone(),
+                    from_year: filter.from_year,
+                    to_year: filter.to_year,
+                    network: filter.network.clone(),
+                    platform: filter.platform.clone(),
+                    genre: filter.genre.clone(),
+                    tag: filter.tag.clone(),
+                    ..Default::default()
+                };
+                show_filter.matches_show(show)
+            })
+            .flat_map(|show| show.characters.iter().map(move |c| (show, c)))
+            .filter(|(show, character)| filter.matches_character(show, character))
+            .collect();
+        characters.sort_by(|a, b| {
+            let order = match sort {
+                CharacterSort::Name => a.1.name.cmp(&b.1.name),
+                CharacterSort::Show => a.0.name.cmp(&b.0.name),
+                CharacterSort::Role => a.1.role.cmp(&b.1.role),
+                CharacterSort::Gender => a.1.gender.cmp(&b.1.gender),
+            };
+            order
+                .then_with(|| a.1.name.cmp(&b.1.name))
+                .then_with(|| a.0.name.cmp(&b.0.name))
+        });
+        characters
+    }
+
+    /// Case-insensitive search of show and character names, including their other names,
+    /// the network, platforms, genres and tags of shows and relationship kinds, within the
+    /// shows matching `filter`
+    pub fn search(&self, term: &str, filter: &Filter) -> Vec<SearchResult<'_>> {
+        let term = term.to_lowercase();
+        let mut shows: Vec<&Show> = self
+            .shows
+            .values()
+            .filter(|show| filter.matches_show(show))
+            .collect();
+        shows.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut results = Vec::new();
+        for show in shows {
+            let contains = |name: &str| name.to_lowercase().contains(&term);
+            let metadata = show
+                .network
+                .iter()
+                .chain(show.platforms.iter())
+                .chain(show.genres.iter())
+                .chain(show.tags.iter())
+                .map(String::as_str);
+            if contains(&show.name) || show.names.iter().chain(metadata).any(contains) {
+                results.push(SearchResult::Show(show));
+            }
+            for character in show.characters.iter() {
+                if contains(&character.name) || character.names.iter().any(contains) {
+                    results.push(SearchResult::Character(show, character));
+                }
+            }
+            let mut relationships: Vec<&Relationship> = show
+                .relationships
+                .values()
+                .filter(|r| r.kind.to_lowercase().contains(&term))
+                .collect();
+            relationships.sort_by(|a, b| a.id.cmp(&b.id));
+            for relationship in relationships {
+                results.push(SearchResult::Relationship(show, relationship));
+            }
+        }
+        results
+    }
+}
+
+impl FromStr for ShowSort {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(ShowSort::Name),
+            "year" => Ok(ShowSort::Year),
+            "characters" => Ok(ShowSort::Characters),
+            _ => Err(Error::Validation(format!(
+                "Unknown sort '{s}', expected name, year or characters"
+            ))),
+        }
+    }
+}
+
+impl FromStr for CharacterSort {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "name" => Ok(CharacterSort::Name),
+            "show" => Ok(CharacterSort::Show),
+            "role" => Ok(CharacterSort::Role),
+            "gender" => Ok(CharacterSort::Gender),
+            _ => Err(Error::Validation(format!(
+                "Unknown sort '{s}', expected name, show, role or gender"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CharacterSort, Filter, SearchResult, ShowSort};
+    use crate::models::{character::Character, show::Show, show_collection::ShowCollection};
+
+    fn collection() -> ShowCollection {
+        let mut show_collection = ShowCollection::default();
+        let mut goblin = Show::new("Goblin".to_string(), 2016);
+        let kim_shin = goblin
+            .add_character(Character::new(
+                "Kim Shin".to_string(),
+                "protagonist".to_string(),
+                "male".to_string(),
+            ))
+            .unwrap()
+            .id
+            .clone();
+        let eun_tak = goblin
+            .add_character(Character::new(
+                "Ji Eun-tak".to_string(),
+                "protagonist".to_string(),
+                "female".to_string(),
+            ))
+            .unwrap()
+            .id
+            .clone();
+        goblin
+            .set_relationship(kim_shin, eun_tak, "husband".to_string())
+            .unwrap();
+        goblin.network = Some("tvN".to_string());
+        goblin.genres = vec!["Fantasy".to_string(), "Romance".to_string()];
+        show_collection.add(goblin).unwrap();
+        let mut cloy = Show::new("Crash Landing on You".to_string(), 2019);
+        cloy.network = Some("tvN".to_string());
+        cloy.genres = vec!["Romance".to_string()];
+        cloy.platforms = vec!["Netflix".to_string()];
+        show_collection.add(cloy).unwrap();
+        show_collection
+    }
+
+    #[test]
+    fn should_filter_shows_by_year_and_character() {
+        let show_collection = collection();
+        let filter = Filter {
+            from_year: Some(2017),
+            ..Default::default()
+        };
+        let shows = show_collection.list_shows(&filter, ShowSort::Year);
+        assert_eq!(shows.len(), 1);
+        assert_eq!(shows[0].name, "Crash Landing on You");
+
+        let filter = Filter {
+            gender: Some("female".to_string()),
+            ..Default::default()
+        };
+        let shows = show_collection.list_shows(&filter, ShowSort::Name);
+        assert_eq!(shows.len(), 1);
+        assert_eq!(shows[0].name, "Goblin");
+
+        let filter = Filter {
+            network: Some("TVN".to_string()),
+            genre: Some("romance".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(show_collection.list_shows(&filter, ShowSort::Name).len(), 2);
+        let filter = Filter {
+            genre: Some("fantasy".to_string()),
+            ..Default::default()
+        };
+        let shows = show_collection.list_shows(&filter, ShowSort::Name);
+        assert_eq!(shows.len(), 1);
+        assert_eq!(shows[0].name, "Goblin");
+    }
+
+    #[test]
+    fn should_filter_characters_by_relationship() {
+        let show_collection = collection();
+        let filter = Filter {
+            relationship: Some("husband".to_string()),
+            ..Default::default()
+        };
+        let characters = show_collection.list_characters(&filter, CharacterSort::Name);
+        assert_eq!(characters.len(), 2);
+        assert_eq!(characters[0].1.name, "Ji Eun-tak");
+    }
+
+    #[test]
+    fn should_search_across_collection() {
+        let show_collection = collection();
+        let results = show_collection.search("HUS", &Filter::default());
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], SearchResult::Relationship(_, _)));
+        assert_eq!(show_collection.search("n", &Filter::default()).len(), 5);
+
+        let results = show_collection.search("netflix", &Filter::default());
+        assert_eq!(results.len(), 1);
+        assert!(
+            matches!(results[0], SearchResult::Show(show) if show.name == "Crash Landing on You")
+        );
+        let filter = Filter {
+            platform: Some("Netflix".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(show_collection.search("n", &filter).len(), 1);
+    }
+}
@@ -0,0 +1,220 @@
+//! Scores and written reviews of shows and episodes, one per user
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+use super::{episode::Episode, show::Show, show_collection::ShowCollection, watch::EpisodeNumber};
+
+/// Scores range from 1 to this
+pub const MAX_SCORE: u8 = 10;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Review {
+    /// Login of whoever wrote the review
+    pub user: String,
+    #[serde(default)]
+    pub score: Option<u8>,
+    #[serde(default)]
+    pub text: Option<String>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+impl Review {
+    /// The start of the text, cut at a word boundary, e.g. for `show info`
+    pub fn get_excerpt(&self, length: usize) -> Option<String> {
+        let text = self.text.as_ref()?;
+        if text.chars().count() <= length {
+            return Some(text.clone());
+        }
+        let cut: String = text.chars().take(length).collect();
+        let cut = match cut.rfind(char::is_whitespace) {
+            Some(index) if index > 0 => &cut[..index],
+            _ => cut.as_str(),
+        };
+        Some(format!("{}...", cut.trim_end()))
+    }
+}
+
+/// The reviews of a show or episode
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(transparent)]
+pub struct Reviews(Vec<Review>);
+
+impl Reviews {
+    pub fn iter(&self) -> impl Iterator<Item = &Review> {
+        self.0.iter()
+    }
+
+    pub fn get_by_user(&self, user: &str) -> Option<&Review> {
+        self.0.iter().find(|r| r.user == user)
+    }
+
+    /// Average of the scores given, None when nobody has scored it yet
+    pub fn get_score(&self) -> Option<f32> {
+        let scores: Vec<f32> = self
+            .0
+            .iter()
+            .filter_map(|r| r.score)
+            .map(f32::from)
+            .collect();
+        match scores.len() {
+            0 => None,
+            count => Some(scores.iter().sum::<f32>() / count as f32),
+        }
+    }
+
+    pub fn get_rating_count(&self) -> usize {
+        self.0.iter().filter(|r| r.score.is_some()).count()
+    }
+
+    /// The most recently updated review that has text
+    pub fn get_latest_text(&self) -> Option<&Review> {
+        self.0
+            .iter()
+            .filter(|r| r.text.is_some())
+            .max_by_key(|r| r.updated)
+    }
+
+    /// Sets the user's score, keeping any text they wrote
+    pub fn rate(&mut self, user: &str, score: u8, now: DateTime<Utc>) -> Result<&Review, Error> {
+        if !(1..=MAX_SCORE).contains(&score) {
+            return Err(Error::Validation(format!(
+                "Score must be between 1 and {MAX_SCORE}"
+            )));
+        }
+        let review = self.get_or_insert(user, now);
+        review.score = Some(score);
+        review.updated = now;
+        Ok(review)
+    }
+
+    /// Sets the user's review text, keeping any score they gave
+    pub fn review(
+        &mut self,
+        user: &str,
+        text: String,
+        now: DateTime<Utc>,
+    ) -> Result<&Review, Error> {
+        if text.trim().is_empty() {
+            return Err(Error::Validation("Review text is empty".to_string()));
+        }
+        let review = self.get_or_insert(user, now);
+        review.text = Some(text.trim().to_string());
+        review.updated = now;
+        Ok(review)
+    }
+
+    fn get_or_insert(&mut self, user: &str, now: DateTime<Utc>) -> &mut Review {
+        let index = match self.0.iter().position(|r| r.user == user) {
+            Some(index) => index,
+            None => {
+                self.0.push(Review {
+                    user: user.to_string(),
+                    score: None,
+                    text: None,
+                    created: now,
+                    updated: now,
+                });
+                self.0.len() - 1
+            }
+        };
+        &mut self.0[index]
+    }
+}
+
+impl Show {
+    /// Reviews of the show, or of one of its episodes
+    pub fn get_reviews_mut(
+        &mut self,
+        episode: Option<EpisodeNumber>,
+    ) -> Result<&mut Reviews, Error> {
+        let number = match episode {
+            Some(number) => number,
+            None => return Ok(&mut self.reviews),
+        };
+        self.seasons
+            .iter_mut()
+            .find(|s| s.number == number.season)
+            .and_then(|s| s.episodes.iter_mut().find(|e| e.number == number.episode))
+            .map(|e| &mut e.reviews)
+            .ok_or_else(|| Error::not_found("episode", number.to_string()))
+    }
+
+    /// Scored episodes, best first
+    pub fn rank_episodes(&self) -> Vec<(EpisodeNumber, &Episode, f32)> {
+        let mut episodes: Vec<(EpisodeNumber, &Episode, f32)> = self
+            .get_episodes()
+            .into_iter()
+            .filter_map(|(number, episode)| Some((number, episode, episode.reviews.get_score()?)))
+            .collect();
+        // Stable, so equal scores stay in watching order
+        episodes.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+        episodes
+    }
+}
+
+impl ShowCollection {
+    pub fn rate(
+        &mut self,
+        show_name: &str,
+        episode: Option<EpisodeNumber>,
+        user: &str,
+        score: u8,
+    ) -> Result<&Review, Error> {
+        self.get_show_by_name_mut(show_name)?
+            .get_reviews_mut(episode)?
+            .rate(user, score, Utc::now())
+    }
+
+    pub fn review(
+        &mut self,
+        show_name: &str,
+        episode: Option<EpisodeNumber>,
+        user: &str,
+        text: String,
+    ) -> Result<&Review, Error> {
+        self.get_show_by_name_mut(show_name)?
+            .get_reviews_mut(episode)?
+            .review(user, text, Utc::now())
+    }
+
+    /// Scored shows, best first
+    pub fn rank_shows(&self) -> Vec<(&Show, f32)> {
+        let mut shows: Vec<(&Show, f32)> = self
+            .shows
+            .values()
+            .filter_map(|show| Some((show, show.reviews.get_score()?)))
+            .collect();
+        shows.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.total_cmp(a_score).then_with(|| a.name.cmp(&b.name))
+        });
+        shows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::Reviews;
+
+    #[test]
+    fn should_keep_one_review_per_user() {
+        let mut reviews = Reviews::default();
+        let now = Utc::now();
+        reviews.rate("jisoo", 9, now).unwrap();
+        reviews
+            .review("jisoo", "The best goblin story ever told".to_string(), now)
+            .unwrap();
+        reviews.rate("minho", 6, now).unwrap();
+        assert!(reviews.rate("minho", 11, now).is_err());
+
+        assert_eq!(reviews.iter().count(), 2);
+        assert_eq!(reviews.get_by_user("jisoo").unwrap().score, Some(9));
+        assert_eq!(reviews.get_score(), Some(7.5));
+        let latest = reviews.get_latest_text().unwrap();
+        assert_eq!(latest.get_excerpt(12).unwrap(), "The best...");
+    }
+}
@@ -3,18 +3,56 @@ use std::{collections::HashMap, fmt::Display};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::DisplayMoreInfo;
+use crate::{
+    error::{Error, Result},
+    DisplayMoreInfo,
+};
 
-use super::character::Character;
+use super::{
+    actor::Actor,
+    character::Character,
+    episode::{format_runtime, Episode, Season},
+    money::Money,
+    names::{NameVariant, Names},
+    review::{Reviews, MAX_SCORE},
+    watch::WatchProgress,
+};
+
+/// Characters of a review shown by `show info`
+pub const REVIEW_EXCERPT_LENGTH: usize = 80;
 
 /// Represents a TV series or movie
 #[derive(Serialize, Deserialize)]
 pub struct Show {
     pub id: String,
     pub name: String,
+    /// Hangul, romanized and English titles, which lookups match as well
+    #[serde(default)]
+    pub names: Names,
     pub release_year: i16,
     pub characters: Vec<Character>,
     pub relationships: HashMap<String, Relationship>,
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+    /// Watch progress, None until the user starts tracking the show
+    #[serde(default)]
+    pub watch: Option<WatchProgress>,
+    #[serde(default)]
+    pub reviews: Reviews,
+    /// Production budget
+    #[serde(default)]
+    pub budget: Option<Money>,
+    /// Broadcaster it first aired on, e.g. tvN
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Streaming services it's on, e.g. Netflix
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    #[serde(default)]
+    pub genres: Vec<String>,
+    /// Free-form labels, e.g. time-travel
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -30,9 +68,18 @@ impl Show {
         Show {
             id: Uuid::new_v4().to_string(),
             name,
+            names: Names::default(),
             release_year,
             characters: Vec::new(),
             relationships: HashMap::new(),
+            seasons: Vec::new(),
+            watch: None,
+            reviews: Reviews::default(),
+            budget: None,
+            network: None,
+            platforms: Vec::new(),
+            genres: Vec::new(),
+            tags: Vec::new(),
         }
     }
 
@@ -44,35 +91,103 @@ impl Show {
         &self.name
     }
 
-    pub fn add_character(&mut self, character: Character) -> Result<&Character, &'static str> {
+    /// Whether `name` is the show's name or one of its other titles
+    pub fn has_name(&self, name: &str) -> bool {
+        self.name == name || self.names.contains(name)
+    }
+
+    pub fn get_display_name(&self, variant: NameVariant) -> &str {
+        self.names.display(&self.name, variant)
+    }
+
+    pub fn add_character(&mut self, character: Character) -> Result<&Character> {
         // check if character exists
-        if let Some(_) = self
-            .characters
-            .iter()
-            .enumerate()
-            .find(|c| c.1.name == character.name)
-        {
-            return Err("Character already exists");
+        if let Some(name) = self.find_taken_character_name(&character, &character.id) {
+            return Err(Error::duplicate("character", name));
         }
         self.characters.push(character);
         Ok(self.characters.last().unwrap())
     }
 
-    pub fn update_character(
-        &mut self,
-        old_name: &str,
-        character: Character,
-    ) -> Result<&Character, &'static str> {
+    /// Updates the names, role and gender of a character while keeping its id and cast,
+    /// so relationships pointing at the character stay attached
+    pub fn update_character(&mut self, old_name: &str, character: Character) -> Result<&Character> {
         // check if character exists
-        if let Some(index) = self.characters.iter().position(|c| c.name == old_name) {
-            self.characters[index] = character;
-            return Ok(self.characters.get(index).unwrap());
+        let index = match self.characters.iter().position(|c| c.has_name(old_name)) {
+            Some(index) => index,
+            None => return Err(Error::not_found("character", old_name)),
+        };
+        // check new names aren't taken by another character
+        let id = self.characters[index].id.clone();
+        if let Some(name) = self.find_taken_character_name(&character, &id) {
+            return Err(Error::duplicate("character", name));
         }
-        return Err("Character not found");
+        let existing = &mut self.characters[index];
+        existing.name = character.name;
+        existing.names = character.names;
+        existing.role = character.role;
+        existing.gender = character.gender;
+        Ok(existing)
+    }
+
+    /// The first of the character's names another character than the one with `id`
+    /// answers to
+    fn find_taken_character_name<'a>(&self, character: &'a Character, id: &str) -> Option<&'a str> {
+        std::iter::once(character.name.as_str())
+            .chain(character.names.iter())
+            .find(|name| {
+                self.characters
+                    .iter()
+                    .any(|c| c.id != id && c.has_name(name))
+            })
+    }
+
+    /// Removes the character along with every relationship it is part of
+    pub fn remove_character(&mut self, name: &str) -> Result<(Character, Vec<Relationship>)> {
+        let index = match self.characters.iter().position(|c| c.has_name(name)) {
+            Some(index) => index,
+            None => return Err(Error::not_found("character", name)),
+        };
+        let character = self.characters.remove(index);
+        let keys: Vec<String> = self
+            .get_character_relationships(&character.id)
+            .iter()
+            .map(|r| r.id.clone())
+            .collect();
+        let relationships = keys
+            .iter()
+            .filter_map(|key| self.relationships.remove(key))
+            .collect();
+
+        Ok((character, relationships))
+    }
+
+    /// Relationships the character is either the source or target of
+    pub fn get_character_relationships(&self, character_id: &str) -> Vec<&Relationship> {
+        self.relationships
+            .values()
+            .filter(|r| r.source == character_id || r.target == character_id)
+            .collect()
+    }
+
+    /// Describes the relationship using character names, e.g. "A is a friend to B"
+    pub fn describe_relationship(&self, relationship: &Relationship) -> String {
+        let source = self.get_character_by_id(&relationship.source);
+        let target = self.get_character_by_id(&relationship.target);
+        format!(
+            "{} is a {} to {}",
+            source.map_or(relationship.source.as_str(), |c| c.name.as_str()),
+            relationship.kind,
+            target.map_or(relationship.target.as_str(), |c| c.name.as_str()),
+        )
+    }
+
+    pub fn get_character_by_id(&self, id: &str) -> Option<&Character> {
+        self.characters.iter().find(|&c| c.id == id)
     }
 
     pub fn get_character_by_name(&self, name: &str) -> Option<&Character> {
-        self.characters.iter().find(|&c| c.name == name)
+        self.characters.iter().find(|&c| c.has_name(name))
     }
 
     pub fn set_relationship(
@@ -80,10 +195,10 @@ impl Show {
         source: String,
         target: String,
         kind: String,
-    ) -> Result<&Relationship, &'static str> {
+    ) -> Result<&Relationship> {
         let key = format!("{}--{}", source, target);
         match self.relationships.get_mut(&key) {
-            Some(mut relationship) => {
+            Some(relationship) => {
                 relationship.kind = kind;
             }
             None => {
@@ -100,6 +215,226 @@ impl Show {
         Ok(self.relationships.get(&key).unwrap())
     }
 
+    /// Casts an actor as the character with the given name
+    pub fn cast_character(&mut self, character_name: &str, actor_id: String) -> Result<&Character> {
+        match self
+            .characters
+            .iter_mut()
+            .find(|c| c.has_name(character_name))
+        {
+            Some(character) => {
+                character.actor_id = Some(actor_id);
+                Ok(character)
+            }
+            None => Err(Error::not_found("character", character_name)),
+        }
+    }
+
+    pub fn get_season(&self, number: u16) -> Option<&Season> {
+        self.seasons.iter().find(|s| s.number == number)
+    }
+
+    pub fn add_season(&mut self, season: Season) -> Result<&Season> {
+        if self.get_season(season.number).is_some() {
+            return Err(Error::duplicate("season", season.number.to_string()));
+        }
+        let index = self
+            .seasons
+            .iter()
+            .position(|s| s.number > season.number)
+            .unwrap_or(self.seasons.len());
+        self.seasons.insert(index, season);
+        Ok(&self.seasons[index])
+    }
+
+    pub fn update_season(&mut self, number: u16, title: Option<String>) -> Result<&Season> {
+        match self.seasons.iter_mut().find(|s| s.number == number) {
+            Some(season) => {
+                season.title = title;
+                Ok(season)
+            }
+            None => Err(Error::not_found("season", number.to_string())),
+        }
+    }
+
+    pub fn get_episode(&self, season: u16, number: u16) -> Option<&Episode> {
+        self.get_season(season)?
+            .episodes
+            .iter()
+            .find(|e| e.number == number)
+    }
+
+    /// Adds the episode to the season, creating the season if it doesn't exist yet
+    pub fn add_episode(&mut self, season: u16, episode: Episode) -> Result<&Episode> {
+        if self.get_episode(season, episode.number).is_some() {
+            return Err(Error::duplicate(
+                "episode",
+                format!("S{season}E{}", episode.number),
+            ));
+        }
+        if self.get_season(season).is_none() {
+            self.add_season(Season::new(season, None))?;
+        }
+        let episodes = &mut self
+            .seasons
+            .iter_mut()
+            .find(|s| s.number == season)
+            .ok_or_else(|| Error::not_found("season", season.to_string()))?
+            .episodes;
+        let index = episodes
+            .iter()
+            .position(|e| e.number > episode.number)
+            .unwrap_or(episodes.len());
+        episodes.insert(index, episode);
+        Ok(&episodes[index])
+    }
+
+    /// Replaces the details of an episode while keeping its id and number
+    pub fn update_episode(&mut self, season: u16, episode: Episode) -> Result<&Episode> {
+        let existing = self
+            .seasons
+            .iter_mut()
+            .find(|s| s.number == season)
+            .and_then(|s| s.episodes.iter_mut().find(|e| e.number == episode.number));
+        match existing {
+            Some(existing) => {
+                existing.title = episode.title;
+                existing.air_date = episode.air_date;
+                existing.runtime = episode.runtime;
+                existing.synopsis = episode.synopsis;
+                existing.notes = episode.notes;
+                Ok(existing)
+            }
+            None => Err(Error::not_found(
+                "episode",
+                format!("S{season}E{}", episode.number),
+            )),
+        }
+    }
+
+    pub fn get_episode_count(&self) -> usize {
+        self.seasons.iter().map(|s| s.episodes.len()).sum()
+    }
+
+    /// Total runtime in minutes of the episodes whose runtime is known
+    pub fn get_runtime(&self) -> u32 {
+        self.seasons.iter().map(Season::get_runtime).sum()
+    }
+
+    /// Same as [`DisplayMoreInfo::more_info`] but names the actor playing each character,
+    /// shows the `names` variant of show and character names and `budget_note` after the
+    /// budget, e.g. what it converts to
+    pub fn more_info_with_cast(
+        &self,
+        actors: &HashMap<String, Actor>,
+        names: NameVariant,
+        budget_note: Option<&str>,
+    ) -> String {
+        let mut message = format!("{} - {}\n", self.get_display_name(names), self.release_year);
+        let others = self.names.get_others(&self.name, names);
+        if !others.is_empty() {
+            message.push_str(&format!("Also known as: {}\n", others.join(", ")));
+        }
+        if let Some(network) = &self.network {
+            message.push_str(&format!("Network: {network}\n"));
+        }
+        for (label, values) in [
+            ("Platforms", &self.platforms),
+            ("Genres", &self.genres),
+            ("Tags", &self.tags),
+        ] {
+            if !values.is_empty() {
+                message.push_str(&format!("{label}: {}\n", values.join(", ")));
+            }
+        }
+        message.push_str("Characters:\n");
+        for character in self.characters.iter() {
+            message.push_str(&format!(
+                "\t- {} ({}) - {}",
+                character.get_display_name(names),
+                character.gender,
+                character.role
+            ));
+            if let Some(actor) = character.actor_id.as_ref().and_then(|id| actors.get(id)) {
+                message.push_str(&format!(" (played by {})", actor.name));
+            }
+            message.push('\n');
+        }
+        if !self.seasons.is_empty() {
+            message.push_str(&format!(
+                "Episodes: {} in {} season(s), {} total\n",
+                self.get_episode_count(),
+                self.seasons.len(),
+                format_runtime(self.get_runtime()),
+            ));
+        }
+        if let Some(budget) = &self.budget {
+            message.push_str(&format!("Budget: {budget}"));
+            if let Some(note) = budget_note {
+                message.push_str(&format!(" ({note})"));
+            }
+            message.push('\n');
+        }
+        if let Some(watch) = &self.watch {
+            message.push_str(&format!("Status: {}", watch.status));
+            if let Some(last_episode) = watch.last_episode {
+                message.push_str(&format!(", last watched {last_episode}"));
+            }
+            message.push('\n');
+        }
+        if let Some(score) = self.reviews.get_score() {
+            message.push_str(&format!(
+                "Score: {score:.1}/{MAX_SCORE} from {} rating(s)\n",
+                self.reviews.get_rating_count()
+            ));
+        }
+        if let Some(review) = self.reviews.get_latest_text() {
+            if let Some(excerpt) = review.get_excerpt(REVIEW_EXCERPT_LENGTH) {
+                message.push_str(&format!("Review by {}: \"{excerpt}\"\n", review.user));
+            }
+        }
+
+        message
+    }
+
+    /// Relationships whose source or target no longer matches a character in the show
+    pub fn get_orphaned_relationships(&self) -> Vec<&Relationship> {
+        self.relationships
+            .values()
+            .filter(|r| {
+                self.get_character_by_id(&r.source).is_none()
+                    || self.get_character_by_id(&r.target).is_none()
+            })
+            .collect()
+    }
+
+    /// Points an existing relationship at new source and target characters, re-keying it
+    pub fn reattach_relationship(
+        &mut self,
+        key: &str,
+        source: String,
+        target: String,
+    ) -> Result<&Relationship> {
+        let new_key = format!("{}--{}", source, target);
+        if new_key != key && self.relationships.contains_key(&new_key) {
+            return Err(Error::duplicate("relationship", new_key));
+        }
+        let mut relationship = match self.relationships.remove(key) {
+            Some(relationship) => relationship,
+            None => return Err(Error::not_found("relationship", key)),
+        };
+        relationship.id = new_key.clone();
+        relationship.source = source;
+        relationship.target = target;
+        self.relationships.insert(new_key.clone(), relationship);
+
+        Ok(self.relationships.get(&new_key).unwrap())
+    }
+
+    pub fn remove_relationship(&mut self, key: &str) -> Option<Relationship> {
+        self.relationships.remove(key)
+    }
+
     pub fn find_rel(& self, source_id: &str, target_id: &str) -> Option<& Relationship> {
          self.relationships.get(&format!("{}--{}", source_id, target_id))
     }
@@ -113,12 +448,140 @@ impl Display for Show {
 
 impl DisplayMoreInfo for Show {
     fn more_info(&self) -> String {
-        let mut message = String::from(format!("{self}\n"));
-        message.push_str(&format!("Characters:\n"));
-        for character in self.characters.iter() {
-            message.push_str(&format!("\t- {character}\n"));
+        self.more_info_with_cast(&HashMap::new(), NameVariant::default(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Show;
+    use crate::{
+        models::{character::Character, episode::Episode},
+        DisplayMoreInfo,
+    };
+
+    fn character(name: &str) -> Character {
+        Character::new(
+            name.to_string(),
+            "protagonist".to_string(),
+            "female".to_string(),
+        )
+    }
+
+    #[test]
+    fn should_not_reuse_another_characters_names() {
+        let mut show = Show::new("Goblin".to_string(), 2016);
+        let mut kim_shin = character("Kim Shin");
+        kim_shin.names.hangul = Some("김신".to_string());
+        show.add_character(kim_shin).unwrap();
+        show.add_character(character("Ji Eun-tak")).unwrap();
+
+        let mut goblin = character("Goblin");
+        goblin.names.english = vec!["Kim Shin".to_string()];
+        assert!(show.add_character(goblin).is_err());
+        assert!(show.add_character(character("김신")).is_err());
+
+        let mut eun_tak = character("Ji Eun-tak");
+        eun_tak.names.hangul = Some("김신".to_string());
+        assert!(show.update_character("Ji Eun-tak", eun_tak).is_err());
+        // Keeping its own names is fine
+        let mut kim_shin = character("Kim Shin");
+        kim_shin.names.hangul = Some("김신".to_string());
+        show.update_character("김신", kim_shin).unwrap();
+        assert_eq!(show.characters.len(), 2);
+    }
+
+    #[test]
+    fn should_keep_relationships_attached_on_update() {
+        let mut show = Show::new("Goblin".to_string(), 2016);
+        let source = show
+            .add_character(character("Kim Shin"))
+            .unwrap()
+            .id
+            .clone();
+        let target = show
+            .add_character(character("Ji Eun-tak"))
+            .unwrap()
+            .id
+            .clone();
+        show.set_relationship(source.clone(), target.clone(), "husband".to_string())
+            .unwrap();
+
+        let updated = show
+            .update_character("Kim Shin", character("Goblin"))
+            .unwrap();
+
+        assert_eq!(updated.id, source);
+        assert!(show.find_rel(&source, &target).is_some());
+        assert!(show.get_orphaned_relationships().is_empty());
+    }
+
+    #[test]
+    fn should_reattach_orphaned_relationship() {
+        let mut show = Show::new("Goblin".to_string(), 2016);
+        let source = show
+            .add_character(character("Kim Shin"))
+            .unwrap()
+            .id
+            .clone();
+        let target = show
+            .add_character(character("Ji Eun-tak"))
+            .unwrap()
+            .id
+            .clone();
+        show.set_relationship(source.clone(), "gone".to_string(), "husband".to_string())
+            .unwrap();
+        let key = show.get_orphaned_relationships()[0].id.clone();
+
+        show.reattach_relationship(&key, source.clone(), target.clone())
+            .unwrap();
+
+        assert!(show.get_orphaned_relationships().is_empty());
+        assert_eq!(show.find_rel(&source, &target).unwrap().kind, "husband");
+    }
+
+    #[test]
+    fn should_remove_relationships_with_character() {
+        let mut show = Show::new("Goblin".to_string(), 2016);
+        let source = show
+            .add_character(character("Kim Shin"))
+            .unwrap()
+            .id
+            .clone();
+        let target = show
+            .add_character(character("Ji Eun-tak"))
+            .unwrap()
+            .id
+            .clone();
+        show.set_relationship(source.clone(), target.clone(), "husband".to_string())
+            .unwrap();
+        show.set_relationship(target, source, "wife".to_string())
+            .unwrap();
+
+        let (character, relationships) = show.remove_character("Kim Shin").unwrap();
+
+        assert_eq!(character.name, "Kim Shin");
+        assert_eq!(relationships.len(), 2);
+        assert!(show.relationships.is_empty());
+        assert!(show.remove_character("Kim Shin").is_err());
+    }
+
+    #[test]
+    fn should_order_episodes_and_sum_runtime() {
+        let mut show = Show::new("Goblin".to_string(), 2016);
+        for number in [2, 1] {
+            let mut episode = Episode::new(number);
+            episode.runtime = Some(70);
+            show.add_episode(1, episode).unwrap();
         }
+        show.add_episode(2, Episode::new(1)).unwrap();
 
-        message
+        assert!(show.add_episode(1, Episode::new(2)).is_err());
+        assert_eq!(show.seasons[0].episodes[0].number, 1);
+        assert_eq!(show.get_episode_count(), 3);
+        assert_eq!(show.get_runtime(), 140);
+        assert!(show
+            .more_info()
+            .contains("Episodes: 3 in 2 season(s), 2h 20m total"));
     }
 }
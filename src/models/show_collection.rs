@@ -1,50 +1,235 @@
-use std::{collections::HashMap, error::Error, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::config::Config;
+use crate::{
+    config::Config,
+    error::Error,
+    storage::{self, Backend, LockFile, Storage},
+};
 
-use super::{character::Character, show::Show};
+use super::{
+    actor::Actor,
+    character::Character,
+    episode::{Episode, Season},
+    journal::{Change, Entry, EntryKind, Journal, Snapshot},
+    migration::{self, MigrationReport, CURRENT_VERSION},
+    show::{Relationship, Show},
+};
 
-pub const SHOWS_FILE_NAME: &'static str = "shows.json";
+/// Lock shared by every storage backend, see [`LockFile`]
+const LOCK_NAME: &str = "shows";
 
-#[derive(Serialize, Deserialize)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct ShowCollection {
+    /// Schema version of the data file, see [`migration`]
+    #[serde(default)]
+    pub version: u32,
     pub shows: HashMap<String, Show>,
+    #[serde(default)]
+    pub actors: HashMap<String, Actor>,
+    /// Held from [`ShowCollection::load`] until the collection is dropped
+    #[serde(skip)]
+    lock: Option<LockFile>,
+    /// Where the collection was loaded from and is saved to
+    #[serde(skip)]
+    storage: Option<Box<dyn Storage>>,
+    /// State as of the last load or save, diffed into the journal on save
+    #[serde(skip)]
+    snapshot: Snapshot,
+}
+
+/// Result of [`ShowCollection::convert`]
+pub struct ConvertReport {
+    pub from: Backend,
+    pub to: Backend,
+    pub shows: usize,
+    pub actors: usize,
+    /// Where the file of the old backend was moved to
+    pub backup: PathBuf,
 }
 
 impl ShowCollection {
-    pub fn get_file_path() -> PathBuf {
-        let config = Config::new();
-        config.get_data_dir().join(SHOWS_FILE_NAME)
+    fn get_lock_path() -> PathBuf {
+        Config::new().get_data_dir().join(LOCK_NAME)
+    }
+
+    fn open_storage() -> Result<Box<dyn Storage>, Error> {
+        storage::open(&Config::new().get_data_dir())
+    }
+
+    /// Loads the collection for modification, locking out other kd instances until the
+    /// collection is dropped. Files from older versions of kd are upgraded first
+    pub fn load() -> Result<ShowCollection, Error> {
+        let lock = LockFile::acquire(&Self::get_lock_path())?;
+        let mut storage = Self::open_storage()?;
+        let mut show_collection = match storage.read()? {
+            Some(value) => Self::upgrade(storage.as_mut(), value, true)?.0,
+            None => ShowCollection::default(),
+        };
+        show_collection.lock = Some(lock);
+        show_collection.storage = Some(storage);
+        show_collection.snapshot = Snapshot::take(&show_collection)?;
+        Ok(show_collection)
     }
 
-    pub fn load() -> Result<ShowCollection, Box<dyn Error>> {
-        if !Self::get_file_path().exists() {
-            return Ok(ShowCollection {
-                shows: HashMap::new(),
-            });
+    /// Loads the collection without locking. Saves are atomic, so this always sees a
+    /// complete file. Older files are upgraded in memory only
+    pub fn read() -> Result<ShowCollection, Error> {
+        let mut storage = Self::open_storage()?;
+        match storage.read()? {
+            Some(value) => Ok(Self::upgrade(storage.as_mut(), value, false)?.0),
+            None => Ok(ShowCollection::default()),
         }
-        let content = std::fs::read_to_string(Self::get_file_path())?;
-        Ok(serde_json::from_str(&content)?)
     }
 
-    pub fn save(&mut self) -> Result<(), Box<dyn Error>> {
-        let content = serde_json::to_string_pretty(&self)?;
-        match std::fs::write(Self::get_file_path(), content) {
-            Ok(_) => return Ok(()),
-            Err(e) => return Err(Box::new(e)),
+    /// Like [`ShowCollection::read`] but only with the show named `name`, which the SQLite
+    /// backend looks up without reading every show
+    pub fn read_show(name: &str) -> Result<ShowCollection, Error> {
+        let mut storage = Self::open_storage()?;
+        match storage.read_show(name)? {
+            Some(value) => Ok(Self::upgrade(storage.as_mut(), value, false)?.0),
+            None => Ok(ShowCollection::default()),
         }
     }
 
-    pub fn add(&mut self, show: Show) {
-        self.shows.insert(show.get_id().to_string(), show);
+    /// Upgrades the data file to the current schema version, keeping a backup of the old
+    /// file. With `dry_run` only reports what would change
+    pub fn migrate(dry_run: bool) -> Result<MigrationReport, Error> {
+        let _lock = match dry_run {
+            true => None,
+            false => Some(LockFile::acquire(&Self::get_lock_path())?),
+        };
+        let mut storage = Self::open_storage()?;
+        match storage.read()? {
+            Some(value) => Ok(Self::upgrade(storage.as_mut(), value, !dry_run)?.1),
+            None => Ok(MigrationReport {
+                from_version: CURRENT_VERSION,
+                to_version: CURRENT_VERSION,
+                changes: Vec::new(),
+                backup: None,
+            }),
+        }
     }
 
+    /// Runs the migrations on stored data. With `write` an upgraded collection is saved back
+    /// after backing up the old file
+    fn upgrade(
+        storage: &mut dyn Storage,
+        mut value: Value,
+        write: bool,
+    ) -> Result<(ShowCollection, MigrationReport), Error> {
+        let mut report = migration::migrate(&mut value)?;
+        let show_collection: ShowCollection = serde_json::from_value(value)?;
+        if write && report.is_needed() {
+            let path = storage.get_path().to_path_buf();
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(format!(".v{}.bak", report.from_version));
+            let backup = PathBuf::from(backup);
+            std::fs::copy(&path, &backup).map_err(|e| {
+                Error::from(e).context(format!("Unable to back up {path:?} before migrating"))
+            })?;
+            storage.write(&show_collection)?;
+            report.backup = Some(backup);
+        }
+        Ok((show_collection, report))
+    }
+
+    /// Moves the collection to another storage backend. The file of the old backend is
+    /// renamed to `<file>.bak`
+    pub fn convert(to: Backend) -> Result<ConvertReport, Error> {
+        let show_collection = Self::load()?;
+        let data_dir = Config::new().get_data_dir();
+        let from = match &show_collection.storage {
+            Some(storage) => storage.get_backend(),
+            None => return Err(Error::Io("Collection has no storage".to_string())),
+        };
+        if from == to {
+            return Err(Error::Validation(format!("Already using the {to} backend")));
+        }
+        let target_path = data_dir.join(to.get_file_name());
+        if target_path.exists() {
+            return Err(Error::Conflict(format!(
+                "{target_path:?} already exists, move it out of the way first"
+            )));
+        }
+
+        let mut target = storage::open_backend(&data_dir, to)?;
+        target.write(&show_collection)?;
+
+        let source_path = data_dir.join(from.get_file_name());
+        let mut backup = source_path.as_os_str().to_owned();
+        backup.push(".bak");
+        let backup = PathBuf::from(backup);
+        if source_path.exists() {
+            std::fs::rename(&source_path, &backup)?;
+        }
+        Ok(ConvertReport {
+            from,
+            to,
+            shows: show_collection.shows.len(),
+            actors: show_collection.actors.len(),
+            backup,
+        })
+    }
+
+    /// Persists the collection and records what changed since it was loaded in the journal
+    pub fn save(&mut self) -> Result<(), Error> {
+        let changes = self.write()?;
+        if !changes.is_empty() {
+            Journal::open().append(&Entry::new(EntryKind::Change, None, changes))?;
+        }
+        Ok(())
+    }
+
+    /// Persists the collection without journaling, returning what changed since the last
+    /// load or write
+    pub(crate) fn write(&mut self) -> Result<Vec<Change>, Error> {
+        if self.lock.is_none() {
+            self.lock = Some(LockFile::acquire(&Self::get_lock_path())?);
+        }
+        let mut storage = match self.storage.take() {
+            Some(storage) => storage,
+            None => Self::open_storage()?,
+        };
+        self.version = CURRENT_VERSION;
+        let snapshot = Snapshot::take(self)?;
+        let changes = self.snapshot.diff(&snapshot);
+        let result = storage.write_changes(self, &changes);
+        self.storage = Some(storage);
+        result?;
+
+        self.snapshot = snapshot;
+        Ok(changes)
+    }
+
+    /// Adds the show unless its name or one of its other titles is taken by another show
+    pub fn add(&mut self, show: Show) -> Result<&Show, Error> {
+        if let Some(name) = self.find_taken_name(&show, &show.id) {
+            return Err(Error::duplicate("show", name));
+        }
+        let id = show.get_id().to_string();
+        self.shows.insert(id.clone(), show);
+        Ok(self.shows.get(&id).unwrap())
+    }
+
+    /// The first of the show's names or titles another show than the one with `id` answers to
+    fn find_taken_name<'a>(&self, show: &'a Show, id: &str) -> Option<&'a str> {
+        std::iter::once(show.name.as_str())
+            .chain(show.names.iter())
+            .find(|name| {
+                self.shows
+                    .values()
+                    .any(|other| other.id != id && other.has_name(name))
+            })
+    }
+
+    /// Names and other titles of every show
     pub fn get_show_names(&self) -> Vec<&str> {
         self.shows
             .values()
-            .map(|show| show.get_name().as_str())
+            .flat_map(|show| std::iter::once(show.get_name().as_str()).chain(show.names.iter()))
             .collect()
     }
 
@@ -52,14 +237,14 @@ impl ShowCollection {
         &mut self,
         show_name: &str,
         character: Character,
-    ) -> Result<&Character, &str> {
+    ) -> Result<&Character, Error> {
         // Find show with name
         for (_key, show) in self.shows.iter_mut() {
-            if show.get_name() == show_name {
+            if show.has_name(show_name) {
                 return show.add_character(character);
             }
         }
-        Err("Show not found")
+        Err(Error::not_found("show", show_name))
     }
 
     pub fn update_character(
@@ -67,30 +252,303 @@ impl ShowCollection {
         show_name: &str,
         old_name: &str,
         character: Character,
-    ) -> Result<&Character, &str> {
+    ) -> Result<&Character, Error> {
         // Find show with name
         for (_key, show) in self.shows.iter_mut() {
-            if show.get_name() == show_name {
+            if show.has_name(show_name) {
                 return show.update_character(old_name, character);
             }
         }
-        Err("Show not found")
+        Err(Error::not_found("show", show_name))
+    }
+
+    /// Removes the character and its relationships from the show
+    pub fn remove_character(
+        &mut self,
+        show_name: &str,
+        name: &str,
+    ) -> Result<(Character, Vec<Relationship>), Error> {
+        // Find show with name
+        for (_key, show) in self.shows.iter_mut() {
+            if show.has_name(show_name) {
+                return show.remove_character(name);
+            }
+        }
+        Err(Error::not_found("show", show_name))
     }
 
     pub fn get_show_by_name(&self, name: &str) -> Option<&Show> {
-        self.shows.values().find(|&show| show.get_name() == name)
+        self.shows.values().find(|&show| show.has_name(name))
+    }
+
+    pub(crate) fn get_show_by_name_mut(&mut self, name: &str) -> Result<&mut Show, Error> {
+        self.shows
+            .values_mut()
+            .find(|show| show.has_name(name))
+            .ok_or_else(|| Error::not_found("show", name))
     }
 
-    // TODO: Consider not passing a new show object but instead just passing new name and release year
-    pub fn update(&mut self, old_name: &str, show: Show) -> Result<&Show, &str> {
-        let old_show = match self.shows.values().find(|&old_show| old_show.name == old_name ) {
-            Some(old_show) => old_show,
-            None => return Err("Unable to find show with old name")
+    pub fn add_season(&mut self, show_name: &str, season: Season) -> Result<&Season, Error> {
+        self.get_show_by_name_mut(show_name)?.add_season(season)
+    }
+
+    pub fn update_season(
+        &mut self,
+        show_name: &str,
+        number: u16,
+        title: Option<String>,
+    ) -> Result<&Season, Error> {
+        self.get_show_by_name_mut(show_name)?
+            .update_season(number, title)
+    }
+
+    pub fn add_episode(
+        &mut self,
+        show_name: &str,
+        season: u16,
+        episode: Episode,
+    ) -> Result<&Episode, Error> {
+        self.get_show_by_name_mut(show_name)?
+            .add_episode(season, episode)
+    }
+
+    pub fn update_episode(
+        &mut self,
+        show_name: &str,
+        season: u16,
+        episode: Episode,
+    ) -> Result<&Episode, Error> {
+        self.get_show_by_name_mut(show_name)?
+            .update_episode(season, episode)
+    }
+
+    /// Replaces the names, release year and production details of a show with those of
+    /// `show`, keeping its id, characters, episodes and reviews
+    pub fn update(&mut self, old_name: &str, show: Show) -> Result<&Show, Error> {
+        let id = match self.get_show_by_name(old_name) {
+            Some(old_show) => old_show.get_id().to_string(),
+            None => return Err(Error::not_found("show", old_name)),
         };
-        let id = String::from(old_show.get_id());
+        // check none of the new names is taken by another show
+        if let Some(name) = self.find_taken_name(&show, &id) {
+            return Err(Error::duplicate("show", name));
+        }
         let old_show = self.shows.get_mut(&id).unwrap();
         old_show.name = show.name;
+        old_show.names = show.names;
         old_show.release_year = show.release_year;
+        old_show.budget = show.budget;
+        old_show.network = show.network;
+        old_show.platforms = show.platforms;
+        old_show.genres = show.genres;
+        old_show.tags = show.tags;
         Ok(old_show)
     }
+
+    pub fn remove(&mut self, name: &str) -> Result<Show, Error> {
+        let id = match self.get_show_by_name(name) {
+            Some(show) => show.get_id().to_string(),
+            None => return Err(Error::not_found("show", name)),
+        };
+        Ok(self.shows.remove(&id).unwrap())
+    }
+
+    pub fn add_actor(&mut self, actor: Actor) -> Result<&Actor, Error> {
+        // check if actor exists
+        if self
+            .actors
+            .values()
+            .any(|a| a.has_name(&actor.name) && a.birth_year == actor.birth_year)
+        {
+            return Err(Error::duplicate("actor", actor.name));
+        }
+        let id = actor.id.clone();
+        self.actors.insert(id.clone(), actor);
+        Ok(self.actors.get(&id).unwrap())
+    }
+
+    /// Renames the actor or changes their birth year and fee while keeping their id, so the
+    /// characters they were cast as stay attached
+    pub fn update_actor(&mut self, old_name: &str, actor: Actor) -> Result<&Actor, Error> {
+        let id = match self.get_actor_by_name(old_name) {
+            Some(old_actor) => old_actor.id.clone(),
+            None => return Err(Error::not_found("actor", old_name)),
+        };
+        // check the new name and birth year aren't taken by another actor
+        if self
+            .actors
+            .values()
+            .any(|a| a.id != id && a.has_name(&actor.name) && a.birth_year == actor.birth_year)
+        {
+            return Err(Error::duplicate("actor", actor.name));
+        }
+        let old_actor = self.actors.get_mut(&id).unwrap();
+        old_actor.name = actor.name;
+        old_actor.birth_year = actor.birth_year;
+        old_actor.fee = actor.fee;
+        Ok(old_actor)
+    }
+
+    pub fn get_actor_names(&self) -> Vec<&str> {
+        self.actors
+            .values()
+            .map(|actor| actor.name.as_str())
+            .collect()
+    }
+
+    pub fn get_actor_by_name(&self, name: &str) -> Option<&Actor> {
+        self.actors.values().find(|&actor| actor.has_name(name))
+    }
+
+    /// Casts the actor as a character in a show
+    pub fn cast(
+        &mut self,
+        show_name: &str,
+        character_name: &str,
+        actor_name: &str,
+    ) -> Result<&Character, Error> {
+        let actor_id = match self.get_actor_by_name(actor_name) {
+            Some(actor) => actor.id.clone(),
+            None => return Err(Error::not_found("actor", actor_name)),
+        };
+        // Find show with name
+        for (_key, show) in self.shows.iter_mut() {
+            if show.has_name(show_name) {
+                return show.cast_character(character_name, actor_id);
+            }
+        }
+        Err(Error::not_found("show", show_name))
+    }
+
+    /// Every character the actor plays, oldest show first
+    pub fn get_filmography(&self, actor_id: &str) -> Vec<(&Show, &Character)> {
+        let mut roles: Vec<(&Show, &Character)> = self
+            .shows
+            .values()
+            .flat_map(|show| {
+                show.characters
+                    .iter()
+                    .filter(|c| c.actor_id.as_deref() == Some(actor_id))
+                    .map(move |c| (show, c))
+            })
+            .collect();
+        roles.sort_by(|a, b| {
+            a.0.release_year
+                .cmp(&b.0.release_year)
+                .then_with(|| a.0.name.cmp(&b.0.name))
+        });
+        roles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShowCollection;
+    use crate::models::{actor::Actor, character::Character, show::Show};
+
+    #[test]
+    fn should_list_cast_characters_in_filmography() {
+        let mut show_collection = ShowCollection::default();
+        show_collection
+            .add(Show::new("Goblin".to_string(), 2016))
+            .unwrap();
+        show_collection
+            .add_character(
+                "Goblin",
+                Character::new(
+                    "Kim Shin".to_string(),
+                    "protagonist".to_string(),
+                    "male".to_string(),
+                ),
+            )
+            .unwrap();
+        let actor_id = show_collection
+            .add_actor(Actor::new("Gong Yoo".to_string(), 1979))
+            .unwrap()
+            .id
+            .clone();
+
+        show_collection
+            .cast("Goblin", "Kim Shin", "Gong Yoo")
+            .unwrap();
+
+        let filmography = show_collection.get_filmography(&actor_id);
+        assert_eq!(filmography.len(), 1);
+        assert_eq!(filmography[0].1.name, "Kim Shin");
+        assert!(show_collection
+            .cast("Goblin", "Kim Shin", "Nobody")
+            .is_err());
+    }
+
+    #[test]
+    fn should_not_add_show_with_another_shows_name() {
+        let mut show_collection = ShowCollection::default();
+        let mut goblin = Show::new("Goblin".to_string(), 2016);
+        goblin.names.hangul = Some("도깨비".to_string());
+        show_collection.add(goblin).unwrap();
+
+        assert!(show_collection
+            .add(Show::new("Goblin".to_string(), 2016))
+            .is_err());
+        assert!(show_collection
+            .add(Show::new("도깨비".to_string(), 2016))
+            .is_err());
+        let mut guardian = Show::new("Guardian".to_string(), 2016);
+        guardian.names.english = vec!["Goblin".to_string()];
+        assert!(show_collection.add(guardian).is_err());
+        assert_eq!(show_collection.shows.len(), 1);
+    }
+
+    #[test]
+    fn should_not_rename_show_to_another_shows_name() {
+        let mut show_collection = ShowCollection::default();
+        show_collection
+            .add(Show::new("Goblin".to_string(), 2016))
+            .unwrap();
+        let mut cloy = Show::new("Crash Landing on You".to_string(), 2019);
+        cloy.names.hangul = Some("사랑의 불시착".to_string());
+        show_collection.add(cloy).unwrap();
+
+        assert!(show_collection
+            .update(
+                "Goblin",
+                Show::new("Crash Landing on You".to_string(), 2016)
+            )
+            .is_err());
+        let mut goblin = Show::new("Goblin".to_string(), 2016);
+        goblin.names.hangul = Some("사랑의 불시착".to_string());
+        assert!(show_collection.update("Goblin", goblin).is_err());
+
+        let mut goblin = Show::new("Guardian".to_string(), 2016);
+        goblin.names.english = vec!["Goblin".to_string()];
+        show_collection.update("Goblin", goblin).unwrap();
+        assert!(show_collection.get_show_by_name("Guardian").is_some());
+    }
+
+    #[test]
+    fn should_not_rename_actor_to_an_existing_one() {
+        let mut show_collection = ShowCollection::default();
+        show_collection
+            .add_actor(Actor::new("Gong Yoo".to_string(), 1979))
+            .unwrap();
+        show_collection
+            .add_actor(Actor::new("Lee Dong-wook".to_string(), 1981))
+            .unwrap();
+
+        // Keeping their own name is fine
+        let actor = show_collection
+            .update_actor("Gong Yoo", Actor::new("Gong Yoo".to_string(), 1979))
+            .unwrap();
+        assert_eq!(actor.birth_year, 1979);
+        assert!(show_collection
+            .update_actor("Lee Dong-wook", Actor::new("Gong Yoo".to_string(), 1979))
+            .is_err());
+        // Same name with another birth year is another actor
+        show_collection
+            .update_actor("Lee Dong-wook", Actor::new("Gong Yoo".to_string(), 1981))
+            .unwrap();
+        assert!(show_collection
+            .update_actor("Nobody", Actor::new("Nobody".to_string(), 1990))
+            .is_err());
+    }
 }
@@ -0,0 +1,241 @@
+//! Where the user is in each show and when they watched it
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+use super::{episode::Episode, show::Show, show_collection::ShowCollection};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum WatchStatus {
+    PlanToWatch,
+    Watching,
+    Completed,
+    Dropped,
+    OnHold,
+}
+
+impl Display for WatchStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            WatchStatus::PlanToWatch => "plan-to-watch",
+            WatchStatus::Watching => "watching",
+            WatchStatus::Completed => "completed",
+            WatchStatus::Dropped => "dropped",
+            WatchStatus::OnHold => "on-hold",
+        };
+        write!(f, "{status}")
+    }
+}
+
+impl FromStr for WatchStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "plan-to-watch" => Ok(WatchStatus::PlanToWatch),
+            "watching" => Ok(WatchStatus::Watching),
+            "completed" => Ok(WatchStatus::Completed),
+            "dropped" => Ok(WatchStatus::Dropped),
+            "on-hold" => Ok(WatchStatus::OnHold),
+            _ => Err(Error::Validation(format!(
+                "Unknown status '{s}', expected plan-to-watch, watching, completed, dropped or on-hold"
+            ))),
+        }
+    }
+}
+
+/// Season and episode number, e.g. S1E3
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct EpisodeNumber {
+    pub season: u16,
+    pub episode: u16,
+}
+
+impl Display for EpisodeNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "S{}E{}", self.season, self.episode)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WatchProgress {
+    pub status: WatchStatus,
+    /// Most recent episode watched, episodes are watched in order
+    #[serde(default)]
+    pub last_episode: Option<EpisodeNumber>,
+    #[serde(default)]
+    pub started: Option<NaiveDate>,
+    #[serde(default)]
+    pub finished: Option<NaiveDate>,
+    /// When progress was last recorded
+    #[serde(default)]
+    pub last_watched: Option<DateTime<Utc>>,
+}
+
+impl WatchProgress {
+    pub fn new(status: WatchStatus) -> WatchProgress {
+        WatchProgress {
+            status,
+            last_episode: None,
+            started: None,
+            finished: None,
+            last_watched: None,
+        }
+    }
+}
+
+impl Show {
+    /// Every episode in watching order
+    pub fn get_episodes(&self) -> Vec<(EpisodeNumber, &Episode)> {
+        self.seasons
+            .iter()
+            .flat_map(|season| {
+                season.episodes.iter().map(|episode| {
+                    let number = EpisodeNumber {
+                        season: season.number,
+                        episode: episode.number,
+                    };
+                    (number, episode)
+                })
+            })
+            .collect()
+    }
+
+    /// Number of episodes up to and including the last one watched
+    pub fn get_watched_count(&self) -> usize {
+        let last = match self.watch.as_ref().and_then(|w| w.last_episode) {
+            Some(last) => last,
+            None => return 0,
+        };
+        self.get_episodes()
+            .iter()
+            .position(|(number, _)| *number == last)
+            .map_or(0, |index| index + 1)
+    }
+
+    /// The episode after the last one watched
+    pub fn get_next_episode(&self) -> Option<(EpisodeNumber, &Episode)> {
+        let episodes = self.get_episodes();
+        let index = self.get_watched_count();
+        episodes.into_iter().nth(index)
+    }
+
+    /// Marks the next episode as watched, completing the show after its final episode
+    pub fn watch_next(&mut self, now: DateTime<Utc>) -> Result<&WatchProgress, Error> {
+        if self.get_episode_count() == 0 {
+            return Err(Error::Validation(format!(
+                "{} has no episodes yet, add them with kd episode add",
+                self.name
+            )));
+        }
+        let next = match self.get_next_episode() {
+            Some((number, _)) => number,
+            None => {
+                return Err(Error::Validation(format!(
+                    "Already watched every episode of {}",
+                    self.name
+                )))
+            }
+        };
+        let is_last = self.get_watched_count() + 1 == self.get_episode_count();
+
+        let today = now.date_naive();
+        let watch = self
+            .watch
+            .get_or_insert_with(|| WatchProgress::new(WatchStatus::Watching));
+        watch.last_episode = Some(next);
+        watch.last_watched = Some(now);
+        watch.started.get_or_insert(today);
+        if is_last {
+            watch.status = WatchStatus::Completed;
+            watch.finished = Some(today);
+        } else {
+            watch.status = WatchStatus::Watching;
+        }
+        Ok(watch)
+    }
+
+    /// Sets the status, recording the start or finish date where it applies
+    pub fn set_watch_status(&mut self, status: WatchStatus, now: DateTime<Utc>) -> &WatchProgress {
+        let today = now.date_naive();
+        let watch = self.watch.get_or_insert_with(|| WatchProgress::new(status));
+        watch.status = status;
+        match status {
+            WatchStatus::Watching => {
+                watch.started.get_or_insert(today);
+                watch.finished = None;
+            }
+            WatchStatus::Completed => {
+                watch.started.get_or_insert(today);
+                watch.finished = Some(today);
+            }
+            WatchStatus::PlanToWatch | WatchStatus::Dropped | WatchStatus::OnHold => {}
+        }
+        watch
+    }
+}
+
+impl ShowCollection {
+    pub fn watch_next(&mut self, show_name: &str) -> Result<&WatchProgress, Error> {
+        self.get_show_by_name_mut(show_name)?.watch_next(Utc::now())
+    }
+
+    pub fn set_watch_status(
+        &mut self,
+        show_name: &str,
+        status: WatchStatus,
+    ) -> Result<&WatchProgress, Error> {
+        Ok(self
+            .get_show_by_name_mut(show_name)?
+            .set_watch_status(status, Utc::now()))
+    }
+
+    /// Shows with the status, most recently watched first
+    pub fn list_watching(&self, status: WatchStatus) -> Vec<&Show> {
+        let mut shows: Vec<&Show> = self
+            .shows
+            .values()
+            .filter(|show| show.watch.as_ref().is_some_and(|w| w.status == status))
+            .collect();
+        shows.sort_by(|a, b| {
+            let last_watched = |show: &Show| show.watch.as_ref().and_then(|w| w.last_watched);
+            last_watched(b)
+                .cmp(&last_watched(a))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        shows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::WatchStatus;
+    use crate::models::{episode::Episode, show::Show};
+
+    #[test]
+    fn should_complete_after_final_episode() {
+        let mut show = Show::new("Goblin".to_string(), 2016);
+        assert!(show.watch_next(Utc::now()).is_err());
+        show.add_episode(1, Episode::new(1)).unwrap();
+        show.add_episode(1, Episode::new(2)).unwrap();
+
+        let first = Utc.with_ymd_and_hms(2016, 12, 2, 21, 0, 0).unwrap();
+        let watch = show.watch_next(first).unwrap();
+        assert!(watch.status == WatchStatus::Watching);
+        assert_eq!(watch.last_episode.unwrap().to_string(), "S1E1");
+        assert_eq!(show.get_next_episode().unwrap().0.to_string(), "S1E2");
+
+        let second = Utc.with_ymd_and_hms(2016, 12, 3, 21, 0, 0).unwrap();
+        let watch = show.watch_next(second).unwrap();
+        assert!(watch.status == WatchStatus::Completed);
+        assert_eq!(watch.started, Some(first.date_naive()));
+        assert_eq!(watch.finished, Some(second.date_naive()));
+        assert!(show.watch_next(second).is_err());
+    }
+}
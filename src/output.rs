@@ -0,0 +1,212 @@
+//! Rendering of command results as text, JSON, YAML or aligned tables
+use std::{fmt::Display, str::FromStr};
+
+use kd::Error;
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+/// How command results are written to stdout
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Yaml,
+    Table,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" => Ok(OutputFormat::Yaml),
+            "table" => Ok(OutputFormat::Table),
+            _ => Err(Error::Validation(format!(
+                "Unknown format '{s}', expected text, json, yaml or table"
+            ))),
+        }
+    }
+}
+
+/// A command result that can be written in every [`OutputFormat`]
+pub trait Render: Serialize {
+    /// Human readable form used by the text format
+    fn text(&self) -> String;
+
+    /// Column names used by the table format
+    fn headers() -> Vec<&'static str>
+    where
+        Self: Sized;
+
+    /// Rows used by the table format
+    fn rows(&self) -> Vec<Vec<String>>;
+}
+
+impl<T: Render> Render for Vec<T> {
+    fn text(&self) -> String {
+        self.iter().map(Render::text).collect::<Vec<_>>().join("\n")
+    }
+
+    fn headers() -> Vec<&'static str> {
+        T::headers()
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.iter().flat_map(Render::rows).collect()
+    }
+}
+
+/// A plain status message, e.g. when the user cancels at a confirmation prompt
+#[derive(Serialize)]
+pub struct Message {
+    pub message: String,
+}
+
+impl Message {
+    pub fn new(message: impl Into<String>) -> Message {
+        Message {
+            message: message.into(),
+        }
+    }
+}
+
+impl Render for Message {
+    fn text(&self) -> String {
+        self.message.clone()
+    }
+
+    fn headers() -> Vec<&'static str> {
+        vec!["Message"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![self.message.clone()]]
+    }
+}
+
+/// Columns padded to the widest cell, by the columns it takes in a terminal so Hangul lines up
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.width()).collect();
+        for row in self.rows.iter() {
+            for (index, cell) in row.iter().enumerate() {
+                if index < widths.len() {
+                    widths[index] = widths[index].max(cell.width());
+                }
+            }
+        }
+
+        let headers: Vec<String> = self.headers.iter().map(|h| h.to_string()).collect();
+        let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        let lines = [&headers, &separator].into_iter().chain(self.rows.iter());
+        for line in lines {
+            let cells: Vec<String> = line
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| {
+                    let padding = width.saturating_sub(cell.width());
+                    format!("{cell}{}", " ".repeat(padding))
+                })
+                .collect();
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+pub fn print<T: Render>(format: OutputFormat, value: &T) -> Result<(), Error> {
+    match format {
+        OutputFormat::Text => println!("{}", value.text()),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(value)?),
+        OutputFormat::Table => print!(
+            "{}",
+            Table {
+                headers: T::headers(),
+                rows: value.rows(),
+            }
+        ),
+    }
+    Ok(())
+}
+
+/// Writes the error to stderr, structured for the JSON and YAML formats
+pub fn print_error(format: OutputFormat, error: &Error) {
+    match structure_error(format, error) {
+        Some(structured) => eprintln!("{}", structured.trim_end()),
+        None => eprintln!("{error}"),
+    }
+}
+
+/// The error with its kind and exit code, so scripts can tell errors apart without parsing
+/// the message. None for the text and table formats
+fn structure_error(format: OutputFormat, error: &Error) -> Option<String> {
+    #[derive(Serialize)]
+    struct ErrorBody {
+        kind: &'static str,
+        message: String,
+        exit_code: i32,
+    }
+    #[derive(Serialize)]
+    struct ErrorOutput {
+        error: ErrorBody,
+    }
+
+    let output = ErrorOutput {
+        error: ErrorBody {
+            kind: error.kind(),
+            message: error.to_string(),
+            exit_code: error.exit_code(),
+        },
+    };
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&output).ok(),
+        OutputFormat::Yaml => serde_yaml::to_string(&output).ok(),
+        OutputFormat::Text | OutputFormat::Table => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kd::Error;
+
+    use super::{structure_error, OutputFormat, Table};
+
+    #[test]
+    fn should_align_table_columns() {
+        let table = Table {
+            headers: vec!["Name", "Year"],
+            rows: vec![
+                vec!["Crash Landing on You".to_string(), "2019".to_string()],
+                vec!["Goblin".to_string(), "2016".to_string()],
+                vec!["도깨비".to_string(), "2016".to_string()],
+            ],
+        };
+        assert_eq!(
+            table.to_string(),
+            "Name                  Year\n\
+             --------------------  ----\n\
+             Crash Landing on You  2019\n\
+             Goblin                2016\n\
+             도깨비                2016\n"
+        );
+    }
+
+    #[test]
+    fn should_structure_errors_with_kind_and_exit_code() {
+        let error = Error::not_found("show", "Goblin").context("Unable to load show");
+        let json = structure_error(OutputFormat::Json, &error).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["error"]["kind"], "not_found");
+        assert_eq!(value["error"]["exit_code"], 3);
+        assert_eq!(value["error"]["message"], error.to_string());
+        assert!(structure_error(OutputFormat::Text, &error).is_none());
+    }
+}
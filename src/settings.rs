@@ -0,0 +1,165 @@
+//! User preferences stored in `config.toml` in the config directory
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use toml::Value;
+
+use crate::{error::Error, korean::exchange::parse_currency, models::names::NameVariant, storage};
+
+pub const FILE_NAME: &str = "config.toml";
+
+/// Keys `kd config` accepts, in the order they're listed
+pub const KEYS: &[&str] = &[
+    "format",
+    "names",
+    "currency",
+    "rates_url",
+    "rates_ttl",
+    "suggestions",
+    "roles",
+    "genders",
+    "editor",
+];
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Output format used when `--format` isn't given
+    pub format: String,
+    /// Which name of shows and characters is displayed
+    pub names: NameVariant,
+    /// Currency amounts are converted to by default
+    pub currency: String,
+    /// Where exchange rates are fetched from, Alpha Vantage when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rates_url: Option<String>,
+    /// Hours a fetched exchange rate is used before it's fetched again
+    pub rates_ttl: u32,
+    /// Number of suggestions shown while typing names
+    pub suggestions: usize,
+    /// Options offered when picking a character's role
+    pub roles: Vec<String>,
+    /// Options offered when picking a character's gender
+    pub genders: Vec<String>,
+    /// Command `kd config edit` opens the file with, `$EDITOR` when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        let strings = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+        Settings {
+            format: "text".to_string(),
+            names: NameVariant::default(),
+            currency: "USD".to_string(),
+            rates_url: None,
+            rates_ttl: 12,
+            suggestions: 5,
+            roles: strings(&["protagonist", "antagonist", "comic-relief"]),
+            genders: strings(&["female", "male", "other"]),
+            editor: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings, using the defaults for anything the file doesn't set
+    pub fn load(config_dir: &Path) -> Result<Settings, Error> {
+        let path = config_dir.join(FILE_NAME);
+        if !path.exists() {
+            return Ok(Settings::default());
+        }
+        let content = std::fs::read_to_string(&path)?;
+        toml::from_str(&content).map_err(|e| Error::from(e).context(format!("Invalid {path:?}")))
+    }
+
+    pub fn save(&self, config_dir: &Path) -> Result<(), Error> {
+        let content = toml::to_string_pretty(self)?;
+        storage::write_atomic(&config_dir.join(FILE_NAME), content.as_bytes())
+    }
+
+    /// Value of `key` as it would be written on the command line, lists comma separated.
+    /// None for unset optional keys
+    pub fn get(&self, key: &str) -> Result<Option<String>, Error> {
+        check_key(key)?;
+        let table = Value::try_from(self)?;
+        Ok(table.get(key).map(|value| match value {
+            Value::String(value) => value.clone(),
+            Value::Array(values) => values
+                .iter()
+                .map(|v| v.as_str().map_or(v.to_string(), String::from))
+                .collect::<Vec<_>>()
+                .join(","),
+            value => value.to_string(),
+        }))
+    }
+
+    /// Parses `value` as the type of `key`, lists are comma separated
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
+        check_key(key)?;
+        let mut table = Value::try_from(&*self)?;
+        let parsed = match key {
+            "suggestions" | "rates_ttl" => Value::Integer(
+                value
+                    .parse()
+                    .map_err(|_| Error::Validation(format!("{key} must be a whole number")))?,
+            ),
+            "names" => Value::String(value.parse::<NameVariant>()?.to_string()),
+            "currency" => Value::String(parse_currency(value)?),
+            "roles" | "genders" => Value::Array(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| Value::String(v.to_string()))
+                    .collect(),
+            ),
+            _ => Value::String(value.to_string()),
+        };
+        if let Value::Table(table) = &mut table {
+            table.insert(key.to_string(), parsed);
+        }
+        *self = table.try_into()?;
+        Ok(())
+    }
+}
+
+fn check_key(key: &str) -> Result<(), Error> {
+    match KEYS.contains(&key) {
+        true => Ok(()),
+        false => Err(Error::Validation(format!(
+            "Unknown setting '{key}', expected one of {}",
+            KEYS.join(", ")
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Settings;
+
+    #[test]
+    fn should_set_typed_values() {
+        let mut settings = Settings::default();
+        settings.set("suggestions", "10").unwrap();
+        settings.set("roles", "lead, villain").unwrap();
+        settings.set("editor", "vim").unwrap();
+        settings.set("names", "Hangul").unwrap();
+        settings.set("rates_ttl", "24").unwrap();
+        settings.set("currency", "eur").unwrap();
+        assert_eq!(settings.suggestions, 10);
+        assert_eq!(settings.rates_ttl, 24);
+        assert_eq!(settings.currency, "EUR");
+        assert_eq!(settings.get("rates_url").unwrap(), None);
+        assert_eq!(settings.roles, vec!["lead", "villain"]);
+        assert_eq!(settings.get("roles").unwrap().unwrap(), "lead,villain");
+        assert_eq!(settings.get("editor").unwrap().unwrap(), "vim");
+        assert_eq!(settings.get("names").unwrap().unwrap(), "hangul");
+
+        assert!(settings.set("suggestions", "many").is_err());
+        assert!(settings.get("colour").is_err());
+        assert!(settings.set("names", "japanese").is_err());
+        assert!(settings.set("currency", "euro").is_err());
+    }
+}
@@ -0,0 +1,64 @@
+//! The collection as a single pretty-printed JSON file
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    models::{names::Names, show_collection::ShowCollection},
+};
+
+use super::{write_atomic, Backend, Storage};
+
+pub const FILE_NAME: &str = "shows.json";
+
+pub struct JsonStorage {
+    path: PathBuf,
+}
+
+impl JsonStorage {
+    pub fn new(path: PathBuf) -> JsonStorage {
+        JsonStorage { path }
+    }
+}
+
+impl Storage for JsonStorage {
+    fn get_backend(&self) -> Backend {
+        Backend::Json
+    }
+
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<Option<Value>, Error> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    fn read_show(&self, name: &str) -> Result<Option<Value>, Error> {
+        let mut value = match self.read()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        // Without an index every show has to be read anyway
+        if let Some(shows) = value.get_mut("shows").and_then(Value::as_object_mut) {
+            shows.retain(|_, show| {
+                let names: Names = show
+                    .get("names")
+                    .and_then(|names| serde_json::from_value(names.clone()).ok())
+                    .unwrap_or_default();
+                show.get("name").and_then(Value::as_str) == Some(name) || names.contains(name)
+            });
+        }
+        Ok(Some(value))
+    }
+
+    fn write(&mut self, show_collection: &ShowCollection) -> Result<(), Error> {
+        let content = serde_json::to_string_pretty(show_collection)?;
+        write_atomic(&self.path, content.as_bytes())
+    }
+}
@@ -0,0 +1,188 @@
+//! Backends the show collection is persisted with, plus crash-safe file writes and the
+//! lock that keeps kd instances from clobbering each other
+use std::{
+    fmt::Display,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use fs2::FileExt;
+use serde_json::Value;
+
+use crate::{
+    error::Error,
+    models::{journal::Change, show_collection::ShowCollection},
+};
+
+pub mod json;
+pub mod sqlite;
+
+/// Where a [`ShowCollection`] is persisted
+///
+/// Backends hand out the stored data as a JSON document shaped like `shows.json`, so
+/// [`crate::models::migration`] can upgrade it the same way whatever the backend
+pub trait Storage {
+    fn get_backend(&self) -> Backend;
+
+    /// File the collection is stored in
+    fn get_path(&self) -> &Path;
+
+    /// Reads the whole collection, None if nothing has been stored yet
+    fn read(&self) -> Result<Option<Value>, Error>;
+
+    /// Reads the show with this name or other title along with every actor, leaving out
+    /// the other shows
+    fn read_show(&self, name: &str) -> Result<Option<Value>, Error>;
+
+    /// Persists the collection, replacing what was stored before
+    fn write(&mut self, show_collection: &ShowCollection) -> Result<(), Error>;
+
+    /// Persists only the records in `changes`, the others being stored already. Backends
+    /// that can't write single records write the whole collection
+    fn write_changes(
+        &mut self,
+        show_collection: &ShowCollection,
+        _changes: &[Change],
+    ) -> Result<(), Error> {
+        self.write(show_collection)
+    }
+}
+
+/// The available [`Storage`] implementations
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Json,
+    Sqlite,
+}
+
+impl Backend {
+    pub fn get_file_name(&self) -> &'static str {
+        match self {
+            Backend::Json => json::FILE_NAME,
+            Backend::Sqlite => sqlite::FILE_NAME,
+        }
+    }
+}
+
+impl Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Backend::Json => write!(f, "json"),
+            Backend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+impl FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Backend::Json),
+            "sqlite" => Ok(Backend::Sqlite),
+            _ => Err(Error::Validation(format!(
+                "Unknown backend '{s}', expected json or sqlite"
+            ))),
+        }
+    }
+}
+
+/// Opens the storage in `data_dir`. A SQLite database takes precedence over a JSON file
+pub fn open(data_dir: &Path) -> Result<Box<dyn Storage>, Error> {
+    if data_dir.join(sqlite::FILE_NAME).exists() {
+        open_backend(data_dir, Backend::Sqlite)
+    } else {
+        open_backend(data_dir, Backend::Json)
+    }
+}
+
+/// Opens the storage of `backend` in `data_dir`, creating it on first write
+pub fn open_backend(data_dir: &Path, backend: Backend) -> Result<Box<dyn Storage>, Error> {
+    let path = data_dir.join(backend.get_file_name());
+    Ok(match backend {
+        Backend::Json => Box::new(json::JsonStorage::new(path)),
+        Backend::Sqlite => Box::new(sqlite::SqliteStorage::open(path)?),
+    })
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over `path`, so
+/// readers see either the old or the new file but never a partial one
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Error> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::Validation(format!("Not a file path: {path:?}")))?
+        .to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{file_name}.{}.tmp", std::process::id()));
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(Error::from(e).context(format!("Unable to write {path:?}")));
+    }
+    Ok(())
+}
+
+/// Exclusive advisory lock on `<file>.lock`, released when dropped
+pub struct LockFile {
+    file: File,
+}
+
+impl LockFile {
+    /// Locks `path` without waiting, failing if another process holds the lock
+    pub fn acquire(path: &Path) -> Result<LockFile, Error> {
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| {
+                Error::from(e).context(format!("Unable to open lock file {lock_path:?}"))
+            })?;
+        if file.try_lock_exclusive().is_err() {
+            return Err(Error::Conflict(format!(
+                "Another kd instance is using {path:?}, try again once it has finished"
+            )));
+        }
+        Ok(LockFile { file })
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_atomic, LockFile};
+    use crate::testing::TempDir;
+
+    #[test]
+    fn should_write_atomically_under_lock() {
+        let dir = TempDir::new();
+        let path = dir.path().join("shows.json");
+
+        let lock = LockFile::acquire(&path).unwrap();
+        assert!(LockFile::acquire(&path).is_err());
+        write_atomic(&path, b"{}").unwrap();
+        write_atomic(&path, b"{\"shows\":{}}").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"shows\":{}}");
+        // Only the data file and its lock are left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        drop(lock);
+        assert!(LockFile::acquire(&path).is_ok());
+    }
+}
@@ -0,0 +1,283 @@
+//! The collection in an embedded SQLite database
+//!
+//! Shows and actors are stored one row each, with the columns used for lookups indexed and
+//! the full record kept as JSON so new fields don't need schema changes. Saves only touch
+//! the rows of the records that changed since the collection was loaded
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{Map, Value};
+
+use crate::{
+    error::Error,
+    models::{
+        actor::Actor,
+        journal::{Change, RecordKind},
+        show::Show,
+        show_collection::ShowCollection,
+    },
+};
+
+use super::{Backend, Storage};
+
+pub const FILE_NAME: &str = "shows.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS shows (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        release_year INTEGER NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS shows_name ON shows (name);
+    CREATE TABLE IF NOT EXISTS actors (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS actors_name ON actors (name);
+";
+
+pub struct SqliteStorage {
+    path: PathBuf,
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    pub fn open(path: PathBuf) -> Result<SqliteStorage, Error> {
+        let connection = Connection::open(&path)
+            .map_err(|e| Error::from(e).context(format!("Unable to open database {path:?}")))?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteStorage { path, connection })
+    }
+
+    fn get_version(&self) -> Result<Option<u32>, Error> {
+        let version: Option<String> = self
+            .connection
+            .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+                row.get(0)
+            })
+            .optional()?;
+        Ok(match version {
+            Some(version) => Some(
+                version
+                    .parse()
+                    .map_err(|_| Error::Parse(format!("Invalid schema version '{version}'")))?,
+            ),
+            None => None,
+        })
+    }
+
+    /// Records selected by `sql` keyed by id
+    fn read_rows(&self, sql: &str, name: Option<&str>) -> Result<Map<String, Value>, Error> {
+        let mut statement = self.connection.prepare(sql)?;
+        let mut rows = match name {
+            Some(name) => statement.query(params![name])?,
+            None => statement.query([])?,
+        };
+        let mut records = Map::new();
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0)?;
+            let data: String = row.get(1)?;
+            records.insert(id, serde_json::from_str(&data)?);
+        }
+        Ok(records)
+    }
+
+    fn document(&self, shows: Map<String, Value>) -> Result<Option<Value>, Error> {
+        let version = match self.get_version()? {
+            Some(version) => version,
+            None => return Ok(None),
+        };
+        let actors = self.read_rows("SELECT id, data FROM actors", None)?;
+        let mut document = Map::new();
+        document.insert("version".to_string(), Value::from(version));
+        document.insert("shows".to_string(), Value::Object(shows));
+        document.insert("actors".to_string(), Value::Object(actors));
+        Ok(Some(Value::Object(document)))
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn get_backend(&self) -> Backend {
+        Backend::Sqlite
+    }
+
+    fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<Option<Value>, Error> {
+        let shows = self.read_rows("SELECT id, data FROM shows", None)?;
+        self.document(shows)
+    }
+
+    fn read_show(&self, name: &str) -> Result<Option<Value>, Error> {
+        // Other titles aren't indexed, the name column catches the common case first
+        let shows = self.read_rows(
+            "SELECT id, data FROM shows WHERE name = ?1
+                OR json_extract(data, '$.names.hangul') = ?1
+                OR json_extract(data, '$.names.romanized') = ?1
+                OR EXISTS (SELECT 1 FROM json_each(data, '$.names.english') WHERE value = ?1)",
+            Some(name),
+        )?;
+        self.document(shows)
+    }
+
+    fn write(&mut self, show_collection: &ShowCollection) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute_batch("DELETE FROM shows; DELETE FROM actors;")?;
+        for show in show_collection.shows.values() {
+            write_show(&transaction, show)?;
+        }
+        for actor in show_collection.actors.values() {
+            write_actor(&transaction, actor)?;
+        }
+        write_version(&transaction, show_collection.version)?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn write_changes(
+        &mut self,
+        show_collection: &ShowCollection,
+        changes: &[Change],
+    ) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+        for change in changes {
+            let id = &change.id;
+            match change.record {
+                RecordKind::Show => match show_collection.shows.get(id) {
+                    Some(show) => write_show(&transaction, show)?,
+                    None => delete(&transaction, "DELETE FROM shows WHERE id = ?1", id)?,
+                },
+                RecordKind::Actor => match show_collection.actors.get(id) {
+                    Some(actor) => write_actor(&transaction, actor)?,
+                    None => delete(&transaction, "DELETE FROM actors WHERE id = ?1", id)?,
+                },
+            }
+        }
+        write_version(&transaction, show_collection.version)?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+fn write_show(connection: &Connection, show: &Show) -> Result<(), Error> {
+    connection
+        .prepare_cached(
+            "INSERT OR REPLACE INTO shows (id, name, release_year, data) VALUES (?1, ?2, ?3, ?4)",
+        )?
+        .execute(params![
+            show.id,
+            show.name,
+            show.release_year,
+            serde_json::to_string(show)?
+        ])?;
+    Ok(())
+}
+
+fn write_actor(connection: &Connection, actor: &Actor) -> Result<(), Error> {
+    connection
+        .prepare_cached("INSERT OR REPLACE INTO actors (id, name, data) VALUES (?1, ?2, ?3)")?
+        .execute(params![actor.id, actor.name, serde_json::to_string(actor)?])?;
+    Ok(())
+}
+
+fn delete(connection: &Connection, sql: &str, id: &str) -> Result<(), Error> {
+    connection.prepare_cached(sql)?.execute(params![id])?;
+    Ok(())
+}
+
+fn write_version(connection: &Connection, version: u32) -> Result<(), Error> {
+    connection.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('version', ?1)",
+        params![version.to_string()],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteStorage;
+    use crate::{
+        models::{journal::Snapshot, show::Show, show_collection::ShowCollection},
+        storage::Storage,
+        testing::TempDir,
+    };
+
+    #[test]
+    fn should_round_trip_collection() {
+        let dir = TempDir::new();
+        let mut storage = SqliteStorage::open(dir.path().join("shows.db")).unwrap();
+        assert!(storage.read().unwrap().is_none());
+
+        let mut show_collection = ShowCollection::default();
+        let mut goblin = Show::new("Goblin".to_string(), 2016);
+        goblin
+            .names
+            .english
+            .push("Guardian: The Lonely and Great God".to_string());
+        show_collection.add(goblin).unwrap();
+        show_collection
+            .add(Show::new("Crash Landing on You".to_string(), 2019))
+            .unwrap();
+        storage.write(&show_collection).unwrap();
+
+        let goblin = storage.read_show("Goblin").unwrap().unwrap();
+        assert_eq!(goblin["shows"].as_object().unwrap().len(), 1);
+        let goblin = storage
+            .read_show("Guardian: The Lonely and Great God")
+            .unwrap()
+            .unwrap();
+        assert_eq!(goblin["shows"].as_object().unwrap().len(), 1);
+
+        let id = show_collection
+            .get_show_by_name("Goblin")
+            .unwrap()
+            .id
+            .clone();
+        show_collection.remove("Goblin").unwrap();
+        storage.write(&show_collection).unwrap();
+        let read: ShowCollection =
+            serde_json::from_value(storage.read().unwrap().unwrap()).unwrap();
+        assert_eq!(read.shows.len(), 1);
+        assert!(!read.shows.contains_key(&id));
+    }
+
+    #[test]
+    fn should_only_write_changed_records() {
+        let dir = TempDir::new();
+        let mut storage = SqliteStorage::open(dir.path().join("shows.db")).unwrap();
+        let mut show_collection = ShowCollection::default();
+        show_collection
+            .add(Show::new("Goblin".to_string(), 2016))
+            .unwrap();
+        show_collection
+            .add(Show::new("Crash Landing on You".to_string(), 2019))
+            .unwrap();
+        show_collection
+            .add(Show::new("Vincenzo".to_string(), 2021))
+            .unwrap();
+        storage.write(&show_collection).unwrap();
+
+        let before = Snapshot::take(&show_collection).unwrap();
+        show_collection.remove("Vincenzo").unwrap();
+        let changes = before.diff(&Snapshot::take(&show_collection).unwrap());
+        // Changed after the diff, so left out of the write
+        show_collection
+            .update("Goblin", Show::new("Dokkaebi".to_string(), 2016))
+            .unwrap();
+        storage.write_changes(&show_collection, &changes).unwrap();
+
+        let read: ShowCollection =
+            serde_json::from_value(storage.read().unwrap().unwrap()).unwrap();
+        assert_eq!(read.shows.len(), 2);
+        assert!(read.get_show_by_name("Goblin").is_some());
+        assert!(read.get_show_by_name("Vincenzo").is_none());
+    }
+}
@@ -0,0 +1,26 @@
+//! Fixtures shared by the unit tests
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+/// A fresh directory under the system temp dir, removed again when dropped, even when the
+/// test fails
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new() -> TempDir {
+        let dir = std::env::temp_dir().join(format!("kd-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}